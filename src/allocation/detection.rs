@@ -0,0 +1,461 @@
+//! Deadlock Detection and Recovery
+//!
+//! Instead of avoiding unsafe states, resources are granted whenever available,
+//! and the system checks from time to time whether some processes wait on each other forever.
+use std::fmt;
+
+use super::{Denial, Statement};
+
+/// Current allocations and outstanding requests, one row per process.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllocationState {
+    available: Vec<usize>,
+    allocation: Vec<Vec<usize>>,
+    request: Vec<Vec<usize>>,
+}
+
+impl AllocationState {
+    pub fn new(available: Vec<usize>) -> Self {
+        Self {
+            available,
+            allocation: vec![],
+            request: vec![],
+        }
+    }
+    /// Adds the next process with what it holds and what it requests.
+    pub fn process(mut self, allocation: Vec<usize>, request: Vec<usize>) -> Result<Self, Denial> {
+        self.set(self.processes(), Some(allocation), Some(request))?;
+        Ok(self)
+    }
+    /// Sets what `pid` holds or requests, adding the processes before it as needed.
+    pub fn set(
+        &mut self,
+        pid: usize,
+        allocation: Option<Vec<usize>>,
+        request: Option<Vec<usize>>,
+    ) -> Result<(), Denial> {
+        let resource_types = self.resource_types();
+        for vector in allocation.iter().chain(&request) {
+            if vector.len() != resource_types {
+                return Err(Denial::Dimension {
+                    expected: resource_types,
+                    found: vector.len(),
+                });
+            }
+        }
+        while self.processes() <= pid {
+            self.allocation.push(vec![0; resource_types]);
+            self.request.push(vec![0; resource_types]);
+        }
+        if let Some(allocation) = allocation {
+            self.allocation[pid] = allocation;
+        }
+        if let Some(request) = request {
+            self.request[pid] = request;
+        }
+        Ok(())
+    }
+    pub fn resource_types(&self) -> usize {
+        self.available.len()
+    }
+    pub fn processes(&self) -> usize {
+        self.allocation.len()
+    }
+    pub fn available(&self) -> &[usize] {
+        &self.available
+    }
+    pub fn allocation(&self, pid: usize) -> &[usize] {
+        &self.allocation[pid]
+    }
+    pub fn request(&self, pid: usize) -> &[usize] {
+        &self.request[pid]
+    }
+    /// Instances of every resource type, free or allocated.
+    pub fn total(&self) -> Vec<usize> {
+        let mut total = self.available.clone();
+        for allocation in &self.allocation {
+            for (total, allocated) in total.iter_mut().zip(allocation) {
+                *total += allocated;
+            }
+        }
+        total
+    }
+    /// Every resource type has a single instance, so a cycle in the wait-for graph is a deadlock.
+    pub fn is_single_instance(&self) -> bool {
+        self.total().iter().all(|&total| total <= 1)
+    }
+    pub fn wait_for_graph(&self) -> WaitForGraph {
+        WaitForGraph::new(self)
+    }
+    /// Detection algorithm, like the safety algorithm with the requests in place of the needs.
+    ///
+    /// A process holding nothing can not be part of a deadlock.
+    /// returns: the processes which can never finish, in order
+    pub fn deadlocked(&self) -> Vec<usize> {
+        let mut work = self.available.clone();
+        let mut finished = self
+            .allocation
+            .iter()
+            .map(|allocation| allocation.iter().all(|&allocated| allocated == 0))
+            .collect::<Vec<_>>();
+        while let Some(pid) = (0..self.processes()).find(|&pid| {
+            !finished[pid]
+                && self.request[pid]
+                    .iter()
+                    .zip(&work)
+                    .all(|(request, work)| request <= work)
+        }) {
+            for (work, allocated) in work.iter_mut().zip(&self.allocation[pid]) {
+                *work += allocated;
+            }
+            finished[pid] = true;
+        }
+        (0..self.processes())
+            .filter(|&pid| !finished[pid])
+            .collect()
+    }
+    /// Searches the wait-for graph for single instance resources, runs the algorithm otherwise,
+    /// both leaving out the processes which hold nothing.
+    pub fn detect(&self) -> Detection {
+        if self.is_single_instance() {
+            let graph = self.wait_for_graph();
+            Detection {
+                deadlocked: graph.deadlocked(),
+                cycle: graph.cycle(),
+            }
+        } else {
+            Detection {
+                deadlocked: self.deadlocked(),
+                cycle: None,
+            }
+        }
+    }
+    fn release_all(&mut self, pid: usize) -> Vec<usize> {
+        let cleared = vec![0; self.resource_types()];
+        let released = std::mem::replace(&mut self.allocation[pid], cleared);
+        for (available, released) in self.available.iter_mut().zip(&released) {
+            *available += released;
+        }
+        released
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Detection {
+    /// processes which can never finish, waiting in a cycle or on a process in one,
+    /// and holding something
+    pub deadlocked: Vec<usize>,
+    /// one cycle of the wait-for graph, found only for single instance resources
+    pub cycle: Option<Vec<usize>>,
+}
+
+impl Detection {
+    pub fn is_deadlocked(&self) -> bool {
+        !self.deadlocked.is_empty()
+    }
+}
+
+/// `Pi -> Pj` if `Pi` requests a resource type which `Pj` holds.
+///
+/// It collapses the resource-allocation graph, the resource nodes and their edges,
+/// which is exact for single instance resources.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaitForGraph {
+    edges: Vec<Vec<usize>>,
+    /// whether each process holds anything
+    holds: Vec<bool>,
+}
+
+impl WaitForGraph {
+    pub fn new(state: &AllocationState) -> Self {
+        let edges = (0..state.processes())
+            .map(|pid| {
+                (0..state.processes())
+                    .filter(|&holder| {
+                        (0..state.resource_types()).any(|resource| {
+                            state.request[pid][resource] > 0
+                                && state.allocation[holder][resource] > 0
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let holds = (0..state.processes())
+            .map(|pid| state.allocation[pid].iter().any(|&allocated| allocated > 0))
+            .collect();
+        Self { edges, holds }
+    }
+    pub fn waits_for(&self, pid: usize) -> &[usize] {
+        &self.edges[pid]
+    }
+    /// A cycle as the processes along it, by depth-first search from the lowest pid.
+    pub fn cycle(&self) -> Option<Vec<usize>> {
+        #[derive(Copy, Clone, PartialEq)]
+        enum Mark {
+            New,
+            OnPath,
+            Done,
+        }
+        fn visit(
+            graph: &WaitForGraph,
+            pid: usize,
+            marks: &mut [Mark],
+            path: &mut Vec<usize>,
+        ) -> Option<Vec<usize>> {
+            marks[pid] = Mark::OnPath;
+            path.push(pid);
+            for &next in &graph.edges[pid] {
+                match marks[next] {
+                    Mark::OnPath => {
+                        let start = path.iter().position(|&p| p == next).unwrap();
+                        return Some(path[start..].to_vec());
+                    }
+                    Mark::New => {
+                        if let Some(cycle) = visit(graph, next, marks, path) {
+                            return Some(cycle);
+                        }
+                    }
+                    Mark::Done => {}
+                }
+            }
+            path.pop();
+            marks[pid] = Mark::Done;
+            None
+        }
+        let mut marks = vec![Mark::New; self.edges.len()];
+        (0..self.edges.len()).find_map(|pid| {
+            if marks[pid] == Mark::New {
+                visit(self, pid, &mut marks, &mut vec![])
+            } else {
+                None
+            }
+        })
+    }
+    /// Processes holding something from which a cycle can be reached.
+    ///
+    /// Like the detection algorithm, a process holding nothing is not deadlocked,
+    /// even if it waits on a cycle, as releasing nothing would not help to break it.
+    pub fn deadlocked(&self) -> Vec<usize> {
+        let on_cycle = |pid: usize| self.reachable(pid).contains(&pid);
+        (0..self.edges.len())
+            .filter(|&pid| self.holds[pid] && self.reachable(pid).into_iter().any(on_cycle))
+            .collect()
+    }
+    /// Processes reachable from `pid` by at least one edge.
+    fn reachable(&self, pid: usize) -> Vec<usize> {
+        let mut seen = vec![false; self.edges.len()];
+        let mut stack = self.edges[pid].clone();
+        while let Some(next) = stack.pop() {
+            if !seen[next] {
+                seen[next] = true;
+                stack.extend(&self.edges[next]);
+            }
+        }
+        (0..self.edges.len()).filter(|&p| seen[p]).collect()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+    /// aborts the cheapest deadlocked process until the deadlock is broken
+    Terminate,
+    /// takes all the resources of the cheapest deadlocked process and rolls it back
+    /// to a checkpoint where it holds nothing, so it has to request them again
+    Preempt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryAction {
+    Terminate { pid: usize, released: Vec<usize> },
+    Preempt { pid: usize, preempted: Vec<usize> },
+}
+
+impl fmt::Display for RecoveryAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecoveryAction::Terminate { pid, released } => {
+                write!(f, "terminate P{}, releasing {:?}", pid, released)
+            }
+            RecoveryAction::Preempt { pid, preempted } => {
+                write!(f, "preempt {:?} from P{} and roll it back", preempted, pid)
+            }
+        }
+    }
+}
+
+/// Breaks deadlocks by picking victims with the minimum cost, ties going to the lowest pid.
+///
+/// The cost of a process is set per process, such as its priority or the work it would lose,
+/// and defaults to the instances it holds. Each rollback adds to the cost,
+/// so the same process is not picked as the victim forever.
+#[derive(Debug, Clone)]
+pub struct Recovery {
+    policy: RecoveryPolicy,
+    costs: Vec<Option<u64>>,
+    rollbacks: Vec<u64>,
+}
+
+impl Recovery {
+    pub fn new(policy: RecoveryPolicy) -> Self {
+        Self {
+            policy,
+            costs: vec![],
+            rollbacks: vec![],
+        }
+    }
+    pub fn cost(mut self, pid: usize, cost: u64) -> Self {
+        if self.costs.len() <= pid {
+            self.costs.resize(pid + 1, None);
+        }
+        self.costs[pid] = Some(cost);
+        self
+    }
+    pub fn rollbacks(&self, pid: usize) -> u64 {
+        self.rollbacks.get(pid).copied().unwrap_or(0)
+    }
+    fn victim_cost(&self, state: &AllocationState, pid: usize) -> u64 {
+        let cost = self
+            .costs
+            .get(pid)
+            .copied()
+            .flatten()
+            .unwrap_or_else(|| state.allocation[pid].iter().sum::<usize>() as u64);
+        cost + self.rollbacks(pid)
+    }
+    /// Picks victims until no process is deadlocked.
+    pub fn recover(&mut self, state: &mut AllocationState) -> Vec<RecoveryAction> {
+        let mut actions = vec![];
+        loop {
+            let deadlocked = state.detect().deadlocked;
+            // a victim holding nothing would release nothing
+            let victim = match deadlocked
+                .into_iter()
+                .filter(|&pid| state.allocation[pid].iter().any(|&allocated| allocated > 0))
+                .min_by_key(|&pid| (self.victim_cost(state, pid), pid))
+            {
+                Some(victim) => victim,
+                None => return actions,
+            };
+            let released = state.release_all(victim);
+            match self.policy {
+                RecoveryPolicy::Terminate => {
+                    state.request[victim] = vec![0; state.resource_types()];
+                    actions.push(RecoveryAction::Terminate {
+                        pid: victim,
+                        released,
+                    });
+                }
+                RecoveryPolicy::Preempt => {
+                    for (request, preempted) in state.request[victim].iter_mut().zip(&released) {
+                        *request += preempted;
+                    }
+                    if self.rollbacks.len() <= victim {
+                        self.rollbacks.resize(victim + 1, 0);
+                    }
+                    self.rollbacks[victim] += 1;
+                    actions.push(RecoveryAction::Preempt {
+                        pid: victim,
+                        preempted: released,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Runs a detection exercise, `available` with the `allocation` and `request` matrices,
+/// printing the deadlocked processes and the recovery under `policy`.
+pub fn run(statements: Vec<Statement>, policy: RecoveryPolicy) -> Result<(), Denial> {
+    let mut state = AllocationState::default();
+    for stmt in statements {
+        match stmt {
+            Statement::Available(available) => state = AllocationState::new(available),
+            Statement::Allocation(pid, allocation) => state.set(pid, Some(allocation), None)?,
+            Statement::Request(pid, request) => state.set(pid, None, Some(request))?,
+            Statement::Max(..) | Statement::Release(..) => {}
+        }
+    }
+    let detection = state.detect();
+    if !detection.is_deadlocked() {
+        println!("no deadlock");
+        return Ok(());
+    }
+    let deadlocked = detection.deadlocked.iter().map(|pid| format!("P{}", pid));
+    println!("deadlocked {}", deadlocked.collect::<Vec<_>>().join(" "));
+    if let Some(cycle) = detection.cycle {
+        let cycle = cycle.iter().map(|pid| format!("P{}", pid));
+        println!("cycle {}", cycle.collect::<Vec<_>>().join(" -> "));
+    }
+    for action in Recovery::new(policy).recover(&mut state) {
+        println!("{}", action);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn textbook() -> Result<AllocationState, Denial> {
+        AllocationState::new(vec![0, 0, 0])
+            .process(vec![0, 1, 0], vec![0, 0, 0])?
+            .process(vec![2, 0, 0], vec![2, 0, 2])?
+            .process(vec![3, 0, 3], vec![0, 0, 0])?
+            .process(vec![2, 1, 1], vec![1, 0, 0])?
+            .process(vec![0, 0, 2], vec![0, 0, 2])
+    }
+
+    #[test]
+    fn multi_instance_detection_and_recovery() {
+        let mut state = textbook().unwrap();
+        assert!(!state.detect().is_deadlocked());
+        assert_eq!(
+            state.set(0, None, Some(vec![1, 0])),
+            Err(Denial::Dimension {
+                expected: 3,
+                found: 2,
+            })
+        );
+        state.request[2] = vec![0, 0, 1];
+        assert_eq!(state.detect().deadlocked, vec![1, 2, 3, 4]);
+        let mut recovery = Recovery::new(RecoveryPolicy::Terminate).cost(1, 1);
+        let actions = recovery.recover(&mut state);
+        assert_eq!(
+            actions,
+            vec![RecoveryAction::Terminate {
+                pid: 1,
+                released: vec![2, 0, 0],
+            }]
+        );
+        assert!(!state.detect().is_deadlocked());
+    }
+
+    #[test]
+    fn single_instance_cycle() -> Result<(), Denial> {
+        // P0 holds R0 and waits for R1, P1 holds R1 and waits for R2, P2 holds R2 and waits for R0,
+        // and P3 waits for R1 without holding anything
+        let mut state = AllocationState::new(vec![0, 0, 0])
+            .process(vec![1, 0, 0], vec![0, 1, 0])?
+            .process(vec![0, 1, 0], vec![0, 0, 1])?
+            .process(vec![0, 0, 1], vec![1, 0, 0])?
+            .process(vec![0, 0, 0], vec![0, 1, 0])?;
+        assert!(state.is_single_instance());
+        let detection = state.detect();
+        assert_eq!(detection.cycle, Some(vec![0, 1, 2]));
+        // both leave out P3, which holds nothing
+        assert_eq!(detection.deadlocked, vec![0, 1, 2]);
+        assert_eq!(state.deadlocked(), detection.deadlocked);
+        let mut recovery = Recovery::new(RecoveryPolicy::Preempt).cost(0, 5);
+        let actions = recovery.recover(&mut state);
+        assert_eq!(
+            actions,
+            vec![RecoveryAction::Preempt {
+                pid: 1,
+                preempted: vec![0, 1, 0],
+            }]
+        );
+        assert_eq!(state.request(1), &[0, 1, 1]);
+        assert_eq!(recovery.rollbacks(1), 1);
+        Ok(())
+    }
+}
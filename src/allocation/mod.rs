@@ -0,0 +1,435 @@
+//! Resource Allocation
+//!
+//! Processes declare the most instances of each resource type they may ever hold,
+//! and the banker grants a request only if all the processes can still finish afterwards,
+//! in some order, whatever they request up to their claims.
+use std::fmt;
+
+pub use parser::{parse, ParseError};
+
+pub mod detection;
+mod parser;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Statement {
+    /// free instances of every resource type
+    Available(Vec<usize>),
+    /// max claim of a process
+    Max(usize, Vec<usize>),
+    /// instances held by a process, already taken out of the available ones
+    Allocation(usize, Vec<usize>),
+    Request(usize, Vec<usize>),
+    Release(usize, Vec<usize>),
+}
+
+/// Why a request or a declaration is denied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Denial {
+    UnknownProcess(usize),
+    /// the vector does not have one entry per resource type
+    Dimension {
+        expected: usize,
+        found: usize,
+    },
+    /// the process asks for more than its max claim, which is an error
+    ExceedsClaim {
+        resource: usize,
+        requested: usize,
+        need: usize,
+    },
+    /// the process has to wait until enough instances are released
+    Unavailable {
+        resource: usize,
+        requested: usize,
+        available: usize,
+    },
+    /// granting would leave no order in which `blocked` can all finish
+    Unsafe {
+        blocked: Vec<usize>,
+    },
+    ExceedsAllocation {
+        resource: usize,
+        released: usize,
+        allocated: usize,
+    },
+    /// an allocation, or a max claim, which leaves the process holding more than its claim
+    AllocationExceedsMax {
+        resource: usize,
+        allocated: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Denial::UnknownProcess(pid) => write!(f, "unknown process P{}", pid),
+            Denial::Dimension { expected, found } => write!(
+                f,
+                "expected {} resource types but found {}",
+                expected, found
+            ),
+            Denial::ExceedsClaim {
+                resource,
+                requested,
+                need,
+            } => write!(
+                f,
+                "requests {} of R{} beyond its max claim, need {}",
+                requested, resource, need
+            ),
+            Denial::Unavailable {
+                resource,
+                requested,
+                available,
+            } => write!(
+                f,
+                "requests {} of R{} but only {} available, has to wait",
+                requested, resource, available
+            ),
+            Denial::Unsafe { blocked } => {
+                let blocked = blocked.iter().map(|pid| format!("P{}", pid));
+                write!(
+                    f,
+                    "unsafe, {} could not finish",
+                    blocked.collect::<Vec<_>>().join(" ")
+                )
+            }
+            Denial::ExceedsAllocation {
+                resource,
+                released,
+                allocated,
+            } => write!(
+                f,
+                "releases {} of R{} but holds {}",
+                released, resource, allocated
+            ),
+            Denial::AllocationExceedsMax {
+                resource,
+                allocated,
+                max,
+            } => write!(
+                f,
+                "holds {} of R{} beyond its max claim {}",
+                allocated, resource, max
+            ),
+        }
+    }
+}
+
+/// The state of the banker, one row per process and one column per resource type.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceManager {
+    available: Vec<usize>,
+    max: Vec<Vec<usize>>,
+    allocation: Vec<Vec<usize>>,
+}
+
+impl ResourceManager {
+    pub fn new(available: Vec<usize>) -> Self {
+        Self {
+            available,
+            max: vec![],
+            allocation: vec![],
+        }
+    }
+    pub fn resource_types(&self) -> usize {
+        self.available.len()
+    }
+    pub fn processes(&self) -> usize {
+        self.max.len()
+    }
+    pub fn available(&self) -> &[usize] {
+        &self.available
+    }
+    pub fn allocation(&self, pid: usize) -> Option<&[usize]> {
+        self.allocation.get(pid).map(Vec::as_slice)
+    }
+    fn check_dimension(&self, vector: &[usize]) -> Result<(), Denial> {
+        if vector.len() == self.resource_types() {
+            Ok(())
+        } else {
+            Err(Denial::Dimension {
+                expected: self.resource_types(),
+                found: vector.len(),
+            })
+        }
+    }
+    fn check_claim(max: &[usize], allocation: &[usize]) -> Result<(), Denial> {
+        for (resource, (&max, &allocated)) in max.iter().zip(allocation).enumerate() {
+            if allocated > max {
+                return Err(Denial::AllocationExceedsMax {
+                    resource,
+                    allocated,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+    fn check_pid(&self, pid: usize) -> Result<(), Denial> {
+        if pid < self.processes() {
+            Ok(())
+        } else {
+            Err(Denial::UnknownProcess(pid))
+        }
+    }
+    /// Adds a process with its max claim, returns its pid.
+    pub fn declare(&mut self, max: Vec<usize>) -> Result<usize, Denial> {
+        self.check_dimension(&max)?;
+        self.allocation.push(vec![0; max.len()]);
+        self.max.push(max);
+        Ok(self.max.len() - 1)
+    }
+    /// Sets the max claim of `pid`, declaring the processes before it as needed.
+    pub fn set_max(&mut self, pid: usize, max: Vec<usize>) -> Result<(), Denial> {
+        self.check_dimension(&max)?;
+        if let Some(allocation) = self.allocation.get(pid) {
+            Self::check_claim(&max, allocation)?;
+        }
+        while self.processes() <= pid {
+            self.declare(vec![0; self.resource_types()])?;
+        }
+        self.max[pid] = max;
+        Ok(())
+    }
+    /// Sets what `pid` holds, without touching the available instances.
+    pub fn set_allocation(&mut self, pid: usize, allocation: Vec<usize>) -> Result<(), Denial> {
+        self.check_dimension(&allocation)?;
+        self.check_pid(pid)?;
+        Self::check_claim(&self.max[pid], &allocation)?;
+        self.allocation[pid] = allocation;
+        Ok(())
+    }
+    /// `Need = Max - Allocation`
+    pub fn need(&self, pid: usize) -> Vec<usize> {
+        self.max[pid]
+            .iter()
+            .zip(&self.allocation[pid])
+            .map(|(max, allocated)| max - allocated)
+            .collect()
+    }
+    /// Safety algorithm, finishes the lowest finishable process first.
+    ///
+    /// returns: the safe sequence, or the processes which could not finish
+    pub fn safe_sequence(&self) -> Result<Vec<usize>, Denial> {
+        let mut work = self.available.clone();
+        let mut finished = vec![false; self.processes()];
+        let mut sequence = vec![];
+        while let Some(pid) = (0..self.processes()).find(|&pid| {
+            !finished[pid]
+                && self
+                    .need(pid)
+                    .iter()
+                    .zip(&work)
+                    .all(|(need, work)| need <= work)
+        }) {
+            for (work, allocated) in work.iter_mut().zip(&self.allocation[pid]) {
+                *work += allocated;
+            }
+            finished[pid] = true;
+            sequence.push(pid);
+        }
+        if sequence.len() == self.processes() {
+            Ok(sequence)
+        } else {
+            Err(Denial::Unsafe {
+                blocked: (0..self.processes())
+                    .filter(|&pid| !finished[pid])
+                    .collect(),
+            })
+        }
+    }
+    pub fn is_safe(&self) -> bool {
+        self.safe_sequence().is_ok()
+    }
+    /// Resource-request algorithm, the state is left untouched if the request is denied.
+    ///
+    /// returns: the safe sequence after granting the request
+    pub fn request(&mut self, pid: usize, request: &[usize]) -> Result<Vec<usize>, Denial> {
+        self.check_pid(pid)?;
+        self.check_dimension(request)?;
+        let need = self.need(pid);
+        for (resource, (&requested, &need)) in request.iter().zip(&need).enumerate() {
+            if requested > need {
+                return Err(Denial::ExceedsClaim {
+                    resource,
+                    requested,
+                    need,
+                });
+            }
+        }
+        for (resource, (&requested, &available)) in request.iter().zip(&self.available).enumerate()
+        {
+            if requested > available {
+                return Err(Denial::Unavailable {
+                    resource,
+                    requested,
+                    available,
+                });
+            }
+        }
+        let mut pretended = self.clone();
+        for (resource, &requested) in request.iter().enumerate() {
+            pretended.available[resource] -= requested;
+            pretended.allocation[pid][resource] += requested;
+        }
+        let sequence = pretended.safe_sequence()?;
+        *self = pretended;
+        Ok(sequence)
+    }
+    pub fn release(&mut self, pid: usize, release: &[usize]) -> Result<(), Denial> {
+        self.check_pid(pid)?;
+        self.check_dimension(release)?;
+        for (resource, (&released, &allocated)) in
+            release.iter().zip(&self.allocation[pid]).enumerate()
+        {
+            if released > allocated {
+                return Err(Denial::ExceedsAllocation {
+                    resource,
+                    released,
+                    allocated,
+                });
+            }
+        }
+        for (resource, &released) in release.iter().enumerate() {
+            self.allocation[pid][resource] -= released;
+            self.available[resource] += released;
+        }
+        Ok(())
+    }
+    /// Runs an exercise, printing the safe sequence of the initial state once the matrices are
+    /// given, then the outcome of every request and release.
+    pub fn run(statements: Vec<Statement>) {
+        let mut manager = Self::default();
+        let mut is_checked = false;
+        for stmt in statements {
+            let outcome = match stmt {
+                Statement::Available(available) => {
+                    manager.available = available;
+                    Ok(())
+                }
+                Statement::Max(pid, max) => manager.set_max(pid, max),
+                Statement::Allocation(pid, allocation) => manager.set_allocation(pid, allocation),
+                Statement::Request(pid, request) => {
+                    manager.check_initial_state(&mut is_checked);
+                    manager.request(pid, &request).map(|sequence| {
+                        println!(
+                            "P{} {:?}: granted, {}",
+                            pid,
+                            request,
+                            format_sequence(&sequence)
+                        );
+                    })
+                }
+                Statement::Release(pid, release) => {
+                    manager.check_initial_state(&mut is_checked);
+                    manager.release(pid, &release).map(|_| {
+                        println!("P{} {:?}: released", pid, release);
+                    })
+                }
+            };
+            if let Err(denial) = outcome {
+                println!("{}", denial);
+            }
+        }
+        manager.check_initial_state(&mut is_checked);
+    }
+    fn check_initial_state(&self, is_checked: &mut bool) {
+        if *is_checked {
+            return;
+        }
+        *is_checked = true;
+        match self.safe_sequence() {
+            Ok(sequence) => println!("safe, {}", format_sequence(&sequence)),
+            Err(denial) => println!("{}", denial),
+        }
+    }
+}
+
+fn format_sequence(sequence: &[usize]) -> String {
+    let sequence = sequence.iter().map(|pid| format!("P{}", pid));
+    format!("sequence {}", sequence.collect::<Vec<_>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTBOOK: &str = "\
+available 3 3 2
+max
+P0 7 5 3
+P1 3 2 2
+P2 9 0 2
+P3 2 2 2
+P4 4 3 3
+allocation
+P0 0 1 0
+P1 2 0 0
+P2 3 0 2
+P3 2 1 1
+P4 0 0 2
+request P1 1 0 2
+request P4 3 3 0
+request P0 0 2 0
+";
+
+    #[test]
+    fn textbook_exercise() {
+        let statements = parse(TEXTBOOK).unwrap();
+        assert_eq!(statements.len(), 14);
+        assert_eq!(statements[6], Statement::Allocation(0, vec![0, 1, 0]));
+        let mut manager = ResourceManager::default();
+        for stmt in statements.iter().take(11).cloned() {
+            match stmt {
+                Statement::Available(available) => manager = ResourceManager::new(available),
+                Statement::Max(pid, max) => manager.set_max(pid, max).unwrap(),
+                Statement::Allocation(pid, allocation) => {
+                    manager.set_allocation(pid, allocation).unwrap()
+                }
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(manager.safe_sequence(), Ok(vec![1, 3, 0, 2, 4]));
+        assert_eq!(manager.request(1, &[1, 0, 2]), Ok(vec![1, 3, 0, 2, 4]));
+        assert_eq!(
+            manager.request(4, &[3, 3, 0]),
+            Err(Denial::Unavailable {
+                resource: 0,
+                requested: 3,
+                available: 2,
+            })
+        );
+        assert_eq!(
+            manager.request(0, &[0, 2, 0]),
+            Err(Denial::Unsafe {
+                blocked: vec![0, 1, 2, 3, 4],
+            })
+        );
+        assert_eq!(manager.available(), &[2, 3, 0]);
+    }
+
+    #[test]
+    fn rejects_typos_and_allocations_beyond_claims() {
+        let err = parse("available 3 3 2\nmax\nP0 7 5 3\nP1 3 2 x\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 4: `P1 3 2 x`: can not parse");
+        let err = parse("available 3 3 2\nP0 7 5 3\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: `P0 7 5 3`: row outside of a matrix"
+        );
+        let mut manager = ResourceManager::new(vec![3, 3, 2]);
+        manager.set_max(0, vec![7, 5, 3]).unwrap();
+        assert_eq!(
+            manager.set_allocation(0, vec![0, 6, 0]),
+            Err(Denial::AllocationExceedsMax {
+                resource: 1,
+                allocated: 6,
+                max: 5,
+            })
+        );
+        manager.set_allocation(0, vec![0, 1, 0]).unwrap();
+        assert!(manager.set_max(0, vec![7, 0, 3]).is_err());
+    }
+}
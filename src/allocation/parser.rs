@@ -0,0 +1,166 @@
+use std::fmt;
+
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, space0, space1};
+use nom::combinator::{all_consuming, map, opt};
+use nom::multi::separated_list;
+use nom::sequence::{preceded, terminated, tuple};
+use nom::IResult;
+
+use super::Statement;
+
+/// A line which is neither a header, a matrix row nor a statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    pub content: String,
+    /// a row with no `max`, `allocation` or `request` header above it
+    pub is_orphan_row: bool,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = if self.is_orphan_row {
+            "row outside of a matrix"
+        } else {
+            "can not parse"
+        };
+        write!(f, "line {}: `{}`: {}", self.line, self.content, reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Copy, Clone)]
+enum Matrix {
+    Max,
+    Allocation,
+    /// outstanding requests, for detection
+    Request,
+}
+
+enum Line {
+    Header(Matrix),
+    /// a row of the current matrix, labeled by its pid or not
+    Row(Option<usize>, Vec<usize>),
+    Statement(Statement),
+}
+
+fn numeric(input: &str) -> IResult<&str, usize> {
+    let (i, n) = take_while1(|c: char| c.is_ascii_digit())(input)?;
+    Ok((i, n.parse().unwrap()))
+}
+
+fn vector(input: &str) -> IResult<&str, Vec<usize>> {
+    terminated(separated_list(space1, numeric), space0)(input)
+}
+
+fn pid(input: &str) -> IResult<&str, usize> {
+    preceded(opt(alt((char('P'), char('p')))), numeric)(input)
+}
+
+/// `P1`, `P1:` or `1:`
+fn label(input: &str) -> IResult<&str, usize> {
+    let prefixed = preceded(alt((char('P'), char('p'))), numeric);
+    let suffixed = terminated(numeric, tuple((space0, char(':'))));
+    terminated(
+        alt((prefixed, suffixed)),
+        tuple((space0, opt(char(':')), space0)),
+    )(input)
+}
+
+/// `P1`, `1` or any label
+fn requester(input: &str) -> IResult<&str, usize> {
+    alt((label, terminated(pid, space1)))(input)
+}
+
+fn header(input: &str) -> IResult<&str, Line> {
+    let matrix = alt((
+        map(tag("max"), |_| Matrix::Max),
+        map(tag("allocation"), |_| Matrix::Allocation),
+        map(tag("request"), |_| Matrix::Request),
+    ));
+    // a bare `request` is a header, not a request statement
+    let (i, (matrix, _, _)) = all_consuming(tuple((matrix, space0, opt(char(':')))))(input)?;
+    Ok((i, Line::Header(matrix)))
+}
+
+fn available(input: &str) -> IResult<&str, Line> {
+    let (i, (_, _, _, _, available)) =
+        tuple((tag("available"), space0, opt(char(':')), space0, vector))(input)?;
+    Ok((i, Line::Statement(Statement::Available(available))))
+}
+
+fn request(input: &str) -> IResult<&str, Line> {
+    let (i, (_, _, pid, request)) = tuple((tag("request"), space1, requester, vector))(input)?;
+    Ok((i, Line::Statement(Statement::Request(pid, request))))
+}
+
+fn release(input: &str) -> IResult<&str, Line> {
+    let (i, (_, _, pid, release)) = tuple((tag("release"), space1, requester, vector))(input)?;
+    Ok((i, Line::Statement(Statement::Release(pid, release))))
+}
+
+fn row(input: &str) -> IResult<&str, Line> {
+    let labeled = map(tuple((label, vector)), |(pid, row)| {
+        Line::Row(Some(pid), row)
+    });
+    let unlabeled = map(vector, |row| Line::Row(None, row));
+    alt((labeled, unlabeled))(input)
+}
+
+/// Parses the usual exercise input, `available` and the `max` and `allocation` matrices,
+/// whose rows are the processes in order, followed by requests and releases.
+/// A `request` matrix lists the outstanding requests of a detection exercise instead.
+///
+/// ```text
+/// available 3 3 2
+/// max
+/// P0 7 5 3
+/// P1 3 2 2
+/// allocation
+/// P0 0 1 0
+/// P1 2 0 0
+/// request P1 1 0 2
+/// release P0 0 1 0
+/// ```
+///
+/// returns: the statements, or the first line which is not one
+pub fn parse(input: &str) -> Result<Vec<Statement>, ParseError> {
+    let mut matrix = None;
+    let mut next_pid = 0;
+    let mut statements = vec![];
+    let lines = input.lines().map(str::trim).enumerate();
+    for (index, content) in lines.filter(|(_, line)| !line.is_empty()) {
+        let error = |is_orphan_row| ParseError {
+            line: index + 1,
+            content: content.to_string(),
+            is_orphan_row,
+        };
+        let line = all_consuming(alt((header, available, request, release, row)))(content);
+        match line.map(|(_, line)| line) {
+            Ok(Line::Header(header)) => {
+                matrix = Some(header);
+                next_pid = 0;
+            }
+            Ok(Line::Row(pid, row)) => {
+                let pid = pid.unwrap_or(next_pid);
+                next_pid = pid + 1;
+                match matrix {
+                    Some(Matrix::Max) => statements.push(Statement::Max(pid, row)),
+                    Some(Matrix::Allocation) => statements.push(Statement::Allocation(pid, row)),
+                    Some(Matrix::Request) => statements.push(Statement::Request(pid, row)),
+                    None => return Err(error(true)),
+                }
+            }
+            Ok(Line::Statement(statement)) => {
+                matrix = None;
+                statements.push(statement);
+            }
+            Err(_) => return Err(error(false)),
+        }
+    }
+    Ok(statements)
+}
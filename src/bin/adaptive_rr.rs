@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::report::Report;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    AdaptiveRoundRobinScheduler, Os, PId, Process, QuantumLog, RoundRobinScheduler, Scheduler,
+};
+
+const BUCKETS: u64 = 10;
+
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..10)
+        .map(|pid| {
+            let job = if pid % 2 == 0 {
+                JobBuilder::new()
+                    .seed(rng.u64(..))
+                    .random_bursts(4, 100..400, 10..50)
+                    .build()
+            } else {
+                JobBuilder::new()
+                    .seed(rng.u64(..))
+                    .random_bursts(8, 5..30, 50..200)
+                    .build()
+            };
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..1000))))
+        })
+        .collect()
+}
+
+fn quantum_over_time(desc: &str, quantum_log: &QuantumLog, clock: u64) -> Report {
+    let quantum_log = quantum_log.lock().expect("lock failed");
+    let mut report = Report::new(&[desc, "Dispatches", "Ave Ready", "Ave Quantum"])
+        .align_right(1)
+        .align_right(2)
+        .align_right(3);
+    let bucket = (clock / BUCKETS).max(1);
+    for i in 0..BUCKETS {
+        let choices = quantum_log
+            .iter()
+            .filter(|c| c.clock.0 / bucket == i || (i == BUCKETS - 1 && c.clock.0 / bucket > i))
+            .collect::<Vec<_>>();
+        let count = choices.len() as u64;
+        let average = |sum: u64| sum.checked_div(count).unwrap_or(0).to_string();
+        report.add_row(vec![
+            format!("{}..{}", i * bucket, (i + 1) * bucket),
+            count.to_string(),
+            average(choices.iter().map(|c| c.ready as u64).sum()),
+            average(choices.iter().map(|c| c.quantum).sum()),
+        ]);
+    }
+    report
+}
+
+/// `adaptive_rr [seed]` compares fixed and adaptive quanta on the same seeded workload
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let queue_length = AdaptiveRoundRobinScheduler::by_queue_length(400, 10);
+    let median_burst = AdaptiveRoundRobinScheduler::by_median_burst(10);
+    let quantum_logs = [
+        ("Queue Length", queue_length.quantum_log()),
+        ("Median Burst", median_burst.quantum_log()),
+    ];
+    let schedulers: Vec<Box<dyn Scheduler + Send>> = vec![
+        Box::new(RoundRobinScheduler::new(20)),
+        Box::new(RoundRobinScheduler::new(100)),
+        Box::new(queue_length),
+        Box::new(median_burst),
+    ];
+    let os_list = schedulers
+        .into_iter()
+        .map(|scheduler| {
+            let mut os = Os::new(processes.clone(), scheduler, "Seeded");
+            os.run();
+            os
+        })
+        .collect::<Vec<_>>();
+    println!("seed: {}", seed);
+    Os::os_list_stats_table(&os_list).printstd();
+    for (os, (desc, quantum_log)) in os_list[2..].iter().zip(quantum_logs.iter()) {
+        let clock = os.timeline().last().map_or(0, |time_slice| time_slice.end.0);
+        quantum_over_time(desc, quantum_log, clock)
+            .to_table()
+            .printstd();
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::long_term::{AdmissionPolicy, LongTermScheduler};
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{Os, PId, Process, RoundRobinScheduler};
+
+/// A burst of CPU-bound and I/O-bound jobs arriving in the first 50 ticks
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..12)
+        .map(|pid| {
+            let builder = JobBuilder::new().seed(rng.u64(..));
+            let job = if rng.bool() {
+                builder.random_bursts(2, 40..120, 5..10)
+            } else {
+                builder.random_bursts(6, 2..8, 30..90)
+            };
+            (pid, Process::new(pid, Arc::new(job.build()), Ticks(rng.u64(..50))))
+        })
+        .collect()
+}
+
+fn get_long_terms() -> Vec<(&'static str, Option<LongTermScheduler>)> {
+    let policies = [
+        ("FCFS", AdmissionPolicy::FirstComeFirstServe),
+        ("Balanced Mix", AdmissionPolicy::BalancedMix),
+        ("SJF", AdmissionPolicy::ShortestJobFirst),
+    ];
+    let mut long_terms = vec![("Unlimited", None)];
+    for (desc, policy) in policies {
+        long_terms.push((desc, Some(LongTermScheduler::new(4, policy))));
+    }
+    long_terms
+}
+
+/// `admission [seed]` compares admission policies under a degree of multiprogramming of 4
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let mut os_list = vec![];
+    for (desc, long_term) in get_long_terms() {
+        let scheduler = Box::new(RoundRobinScheduler::new(10));
+        let mut os = Os::new(processes.clone(), scheduler, desc);
+        if let Some(long_term) = long_term {
+            os = os.with_long_term(long_term);
+        }
+        os.run();
+        os_list.push(os);
+    }
+    println!("seed: {}", seed);
+    Os::os_list_stats_table(&os_list).printstd();
+    for os in &os_list {
+        println!("{}", os.jobs_desc());
+        os.detailed_process_stats_table().printstd();
+    }
+}
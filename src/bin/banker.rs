@@ -0,0 +1,18 @@
+use std::io::Read;
+
+use os_learning::allocation::{parse, ResourceManager};
+
+/// `banker < exercise.txt` runs a Banker's algorithm exercise from stdin
+fn main() {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .expect("failed to read stdin");
+    match parse(&input) {
+        Ok(statements) => ResourceManager::run(statements),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::cache::CacheModel;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    Os, PId, Process, RoundRobinScheduler, Scheduler, ShortestRemainingJobFirstScheduler,
+};
+
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..8)
+        .map(|pid| {
+            let job = JobBuilder::new()
+                .seed(rng.u64(..))
+                .random_bursts(4, 50..300, 10..100)
+                .build();
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..500))))
+        })
+        .collect()
+}
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(RoundRobinScheduler::new(5)),
+        Box::new(RoundRobinScheduler::new(20)),
+        Box::new(RoundRobinScheduler::new(100)),
+        Box::new(ShortestRemainingJobFirstScheduler::new()),
+    ]
+}
+
+/// `cache_warmth [seed]` shows how cold caches punish small quanta
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let cache_model = CacheModel::new(4, 3).cold_after(200);
+    let mut os_list = vec![];
+    for scheduler in get_schedulers() {
+        let mut os = Os::new(processes.clone(), scheduler, "Free Switch");
+        os.run();
+        os_list.push(os);
+    }
+    for scheduler in get_schedulers() {
+        let mut os =
+            Os::new(processes.clone(), scheduler, "Cold Cache").with_cache_model(cache_model);
+        os.run();
+        os_list.push(os);
+    }
+    println!("seed: {}, {:?}", seed, cache_model);
+    Os::os_list_stats_table(&os_list).printstd();
+}
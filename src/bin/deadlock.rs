@@ -0,0 +1,28 @@
+use std::io::Read;
+
+use os_learning::allocation::detection::{run, RecoveryPolicy};
+use os_learning::allocation::parse;
+
+/// `deadlock [terminate|preempt] < exercise.txt` detects deadlocks in an exercise from stdin,
+/// `available` with the `allocation` and `request` matrices, and recovers from them
+fn main() {
+    let policy = match std::env::args().nth(1).as_deref() {
+        Some("preempt") => RecoveryPolicy::Preempt,
+        _ => RecoveryPolicy::Terminate,
+    };
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .expect("failed to read stdin");
+    let statements = match parse(&input) {
+        Ok(statements) => statements,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    if let Err(denial) = run(statements, policy) {
+        eprintln!("{}", denial);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    FirstComeFirstServeScheduler, HighestResponseRatioNextScheduler,
+    MultilevelFeedbackQueueScheduler, Os, PId, Process, RoundRobinScheduler, Scheduler,
+    ShortestRemainingJobFirstScheduler,
+};
+
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..4)
+        .map(|pid| {
+            let job = JobBuilder::new()
+                .seed(rng.u64(..))
+                .random_bursts(2, 2..12, 3..10)
+                .build();
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..10))))
+        })
+        .collect()
+}
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(FirstComeFirstServeScheduler::new()),
+        Box::new(RoundRobinScheduler::new(4)),
+        Box::new(ShortestRemainingJobFirstScheduler::new()),
+        Box::new(HighestResponseRatioNextScheduler::new()),
+        Box::new(MultilevelFeedbackQueueScheduler::new([2, 4])),
+    ]
+}
+
+/// `explain [seed]` narrates why every process was dispatched, alongside the timeline
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    println!("seed: {}", seed);
+    for scheduler in get_schedulers() {
+        let mut os = Os::new(processes.clone(), scheduler, "Explained").with_explanations();
+        os.run();
+        println!("\n{}", os.desc());
+        println!("{}", os.narrative());
+    }
+}
@@ -0,0 +1,62 @@
+use os_learning::scheduling::runtime::{Runtime, Yield};
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    FirstComeFirstServeScheduler, MultilevelFeedbackQueueScheduler, RoundRobinScheduler, Scheduler,
+};
+
+/// Counts primes below `limit`, checking `chunk` numbers between yield points.
+fn count_primes(limit: u64, chunk: u64) -> impl FnMut() -> Yield + Send {
+    let mut n = 2;
+    let mut count = 0;
+    move || {
+        let end = (n + chunk).min(limit);
+        while n < end {
+            if (2..).take_while(|d| d * d <= n).all(|d| n % d != 0) {
+                count += 1;
+            }
+            n += 1;
+        }
+        if n >= limit {
+            log::info!("{} primes below {}", count, limit);
+            Yield::Done
+        } else {
+            Yield::Continue
+        }
+    }
+}
+
+/// Handles `requests` requests, each is a short computation followed by waiting for the user.
+fn interactive(requests: u64) -> impl FnMut() -> Yield + Send {
+    let mut handled = 0;
+    move || {
+        let checksum = (0..10_000u64).fold(0u64, |acc, x| acc.wrapping_mul(31).wrapping_add(x));
+        log::trace!("request {} handled: {}", handled, checksum);
+        handled += 1;
+        if handled >= requests {
+            Yield::Done
+        } else {
+            Yield::Block(5)
+        }
+    }
+}
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(FirstComeFirstServeScheduler::new()),
+        Box::new(RoundRobinScheduler::new(2)),
+        Box::new(MultilevelFeedbackQueueScheduler::new([2, 4])),
+    ]
+}
+
+fn main() {
+    pretty_env_logger::init();
+    for scheduler in get_schedulers() {
+        let mut runtime = Runtime::new(scheduler);
+        runtime.spawn("primes(200000)", count_primes(200_000, 10_000));
+        runtime.spawn("primes(100000)", count_primes(100_000, 10_000));
+        runtime.spawn_at(Ticks(3), "interactive", interactive(5));
+        let report = runtime.run();
+        println!("{}", report.os().desc());
+        report.latency_table().printstd();
+    }
+}
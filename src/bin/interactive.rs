@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::interactive::InteractionStats;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    FirstComeFirstServeScheduler, MultilevelFeedbackQueueScheduler, Os, PId, Process,
+    RoundRobinScheduler, Scheduler, ShortestJobFirstScheduler,
+};
+
+/// Response times longer than it annoy the user.
+const SLA: u64 = 100;
+
+/// Interactive users sharing the CPU with long batch jobs.
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..10)
+        .map(|pid| {
+            let job = if pid % 3 == 0 {
+                JobBuilder::new()
+                    .seed(rng.u64(..))
+                    .random_bursts(2, 500..1500, 10..50)
+                    .build()
+            } else {
+                JobBuilder::new()
+                    .seed(rng.u64(..))
+                    .interactive(20, 2..15, 50..300)
+                    .build()
+            };
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..200))))
+        })
+        .collect()
+}
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(FirstComeFirstServeScheduler::new()),
+        Box::new(ShortestJobFirstScheduler::new()),
+        Box::new(RoundRobinScheduler::new(10)),
+        Box::new(MultilevelFeedbackQueueScheduler::new([20, 100])),
+    ]
+}
+
+/// `interactive [seed]` shows why RR and MLFQ favor interactive users over SJF and FCFS
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let os_list = get_schedulers()
+        .into_iter()
+        .map(|scheduler| {
+            let mut os = Os::new(processes.clone(), scheduler, "Interactive & Batch");
+            os.run();
+            os
+        })
+        .collect::<Vec<_>>();
+    println!("seed: {}", seed);
+    Os::os_list_stats_table(&os_list).printstd();
+    InteractionStats::report(&os_list, SLA)
+        .to_table()
+        .printstd();
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::interrupt::InterruptModel;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    FirstComeFirstServeScheduler, Os, PId, Process, RoundRobinScheduler, Scheduler,
+};
+
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..8)
+        .map(|pid| {
+            let job = JobBuilder::new()
+                .seed(rng.u64(..))
+                .random_bursts(6, 10..200, 20..150)
+                .build();
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..500))))
+        })
+        .collect()
+}
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(FirstComeFirstServeScheduler::new()),
+        Box::new(RoundRobinScheduler::new(20)),
+    ]
+}
+
+/// `interrupts [seed]` splits the CPU time into user and system time under several timer frequencies
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let interrupt_models = vec![
+        ("No Interrupts", None),
+        ("I/O 3", Some(InterruptModel::new().io_completion(3))),
+        (
+            "Timer 50, I/O 3",
+            Some(InterruptModel::new().timer(50, 1).io_completion(3)),
+        ),
+        (
+            "Timer 10, I/O 3",
+            Some(InterruptModel::new().timer(10, 1).io_completion(3)),
+        ),
+    ];
+    let mut os_list = vec![];
+    for (desc, interrupt_model) in interrupt_models {
+        for scheduler in get_schedulers() {
+            let mut os = Os::new(processes.clone(), scheduler, desc);
+            if let Some(interrupt_model) = interrupt_model {
+                os = os.with_interrupts(interrupt_model);
+            }
+            os.run();
+            os_list.push(os);
+        }
+    }
+    println!("seed: {}", seed);
+    Os::os_list_stats_table(&os_list).printstd();
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::interactive::InteractionStats;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    MultilevelFeedbackQueueScheduler, O1Scheduler, Os, PId, Process, RoundRobinScheduler, Scheduler,
+};
+
+const SLA: u64 = 100;
+
+/// Interactive users, batch jobs, and a niced batch job.
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..10)
+        .map(|pid| {
+            let job = if pid % 3 == 0 {
+                JobBuilder::new()
+                    .seed(rng.u64(..))
+                    .random_bursts(2, 500..1500, 10..50)
+                    .build()
+            } else {
+                JobBuilder::new()
+                    .seed(rng.u64(..))
+                    .interactive(20, 2..15, 50..300)
+                    .build()
+            };
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..200))))
+        })
+        .collect()
+}
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(RoundRobinScheduler::new(100)),
+        Box::new(MultilevelFeedbackQueueScheduler::new([20, 100])),
+        Box::new(O1Scheduler::new()),
+        // the first batch job gets the longest time slices, the last one the shortest
+        Box::new(O1Scheduler::new().nice(0, -10).nice(9, 10)),
+    ]
+}
+
+/// `o1 [seed]` compares the O(1) scheduler with round robin and MLFQ
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let os_list = get_schedulers()
+        .into_iter()
+        .map(|scheduler| {
+            let mut os = Os::new(processes.clone(), scheduler, "Interactive & Batch");
+            os.run();
+            os
+        })
+        .collect::<Vec<_>>();
+    println!("seed: {}", seed);
+    Os::os_list_stats_table(&os_list).printstd();
+    InteractionStats::report(&os_list, SLA)
+        .to_table()
+        .printstd();
+    for os in &os_list[2..] {
+        let batch = os
+            .processes()
+            .filter(|p| !p.job.is_interactive())
+            .map(|p| format!("{}: {}", p.id, p.completion_time()))
+            .collect::<Vec<_>>();
+        println!(
+            "{} batch completions {}",
+            os.scheduler_desc(),
+            batch.join(", ")
+        );
+    }
+}
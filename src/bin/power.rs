@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::power::PowerModel;
+use os_learning::scheduling::report::Report;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    EnergyAwareScheduler, FrequencyPolicy, Os, PId, Process, RoundRobinScheduler,
+};
+
+/// `io` ticks of I/O after each CPU burst, the more the lighter the load
+fn workload(seed: u64, io: std::ops::Range<u64>) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..6)
+        .map(|pid| {
+            let job = JobBuilder::new()
+                .seed(rng.u64(..))
+                .random_bursts(5, 10..60, io.clone())
+                .build();
+            (pid, Process::new(pid, Arc::new(job), Ticks(rng.u64(..200))))
+        })
+        .collect()
+}
+
+fn get_policies() -> Vec<(&'static str, FrequencyPolicy)> {
+    vec![
+        ("Race to Idle", FrequencyPolicy::RaceToIdle),
+        (
+            "Slow and Steady",
+            FrequencyPolicy::SlowAndSteady {
+                window: 20,
+                headroom: 25,
+            },
+        ),
+        ("Half Speed", FrequencyPolicy::Fixed(1)),
+    ]
+}
+
+/// `power [seed]` compares racing to idle with slowing down, under a light and a heavy load
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let power_model = PowerModel::mobile();
+    let mut os_list = vec![];
+    for (load, io) in [("Light", 200..800), ("Heavy", 5..40)] {
+        let processes = workload(seed, io);
+        for (desc, policy) in get_policies() {
+            let inner = Box::new(RoundRobinScheduler::new(10));
+            let scheduler = Box::new(EnergyAwareScheduler::new(inner, policy));
+            let jobs_desc = format!("{}, {}", load, desc);
+            let mut os = Os::new(processes.clone(), scheduler, jobs_desc)
+                .with_power_model(power_model.clone());
+            os.run();
+            os_list.push(os);
+        }
+    }
+    println!("seed: {}, {:?}", seed, power_model);
+    Os::os_list_stats_table(&os_list).printstd();
+    let mut report = Report::new(&["Job", "Active", "Idle", "Sleep", "Wake-ups", "Slowed"]);
+    for os in &os_list {
+        let usage = os.power_usage();
+        report.add_row(vec![
+            os.jobs_desc().to_string(),
+            usage.active_time.to_string(),
+            usage.idle_time.to_string(),
+            usage.sleep_time.to_string(),
+            usage.wake_ups.to_string(),
+            usage.slowed_time.to_string(),
+        ]);
+    }
+    report.to_table().printstd();
+}
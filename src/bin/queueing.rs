@@ -0,0 +1,21 @@
+use os_learning::scheduling::queueing::{QueueModel, ServiceTime};
+
+/// `queueing [seed]` compares the measured means of single server queues with queueing theory,
+/// each cell is `predicted / measured (error)`
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let models = vec![
+        QueueModel::mm1(0.02, 20.0),
+        QueueModel::mm1(0.04, 20.0),
+        QueueModel::mg1(0.04, 20.0, ServiceTime::Deterministic),
+        QueueModel::mg1(0.04, 20.0, ServiceTime::Uniform),
+    ];
+    println!("seed: {}", seed);
+    for model in models {
+        model.validation_report(seed).to_table().printstd();
+    }
+}
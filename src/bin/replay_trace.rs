@@ -0,0 +1,47 @@
+use os_learning::scheduling::{
+    FirstComeFirstServeScheduler, HighestResponseRatioNextScheduler, LongestJobFirstScheduler,
+    LongestRemainingJobFirstScheduler, MultilevelFeedbackQueueScheduler, Os, RoundRobinScheduler,
+    Scheduler, ShortestJobFirstScheduler, ShortestRemainingJobFirstScheduler,
+};
+use os_learning::scheduling::trace;
+
+fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
+    vec![
+        Box::new(HighestResponseRatioNextScheduler::new()),
+        Box::new(ShortestJobFirstScheduler::new()),
+        Box::new(ShortestRemainingJobFirstScheduler::new()),
+        Box::new(LongestJobFirstScheduler::new()),
+        Box::new(LongestRemainingJobFirstScheduler::new()),
+        Box::new(FirstComeFirstServeScheduler::new()),
+        Box::new(RoundRobinScheduler::new(100)),
+        Box::new(MultilevelFeedbackQueueScheduler::new([50, 100])),
+    ]
+}
+
+/// `replay_trace <trace.csv>` replays the recorded bursts under every scheduler
+fn main() {
+    pretty_env_logger::init();
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: replay_trace <trace.csv>");
+            std::process::exit(2);
+        }
+    };
+    let processes = match trace::load(&path) {
+        Ok(processes) => processes,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    let os_list = get_schedulers()
+        .into_iter()
+        .map(|scheduler| {
+            let mut os = Os::new(processes.clone(), scheduler, "Trace");
+            os.run();
+            os
+        })
+        .collect::<Vec<_>>();
+    Os::os_list_stats_table(&os_list).printstd();
+}
@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+
+use os_learning::scheduling::job::Job;
+use os_learning::scheduling::result::{self, RunResult};
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{
+    FirstComeFirstServeScheduler, MultilevelFeedbackQueueScheduler, Os, Process,
+    RoundRobinScheduler, Scheduler, ShortestRemainingJobFirstScheduler,
+};
+use std::sync::Arc;
+
+fn get_schedulers() -> Vec<(Box<dyn Scheduler + Send>, &'static str)> {
+    vec![
+        (Box::new(FirstComeFirstServeScheduler::new()), "fcfs"),
+        (Box::new(ShortestRemainingJobFirstScheduler::new()), "srjf"),
+        (Box::new(RoundRobinScheduler::new(100)), "rr-100"),
+        (
+            Box::new(MultilevelFeedbackQueueScheduler::new([50, 100])),
+            "mlfq-50-100",
+        ),
+    ]
+}
+
+fn usage() -> ! {
+    eprintln!("usage: run_archive save <dir>");
+    eprintln!("       run_archive compare <result.json>...");
+    std::process::exit(2);
+}
+
+/// Runs every scheduler over the same workload, saves the workload and one result per scheduler.
+fn save(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let processes = (0..20)
+        .map(|pid| {
+            let job = if pid % 2 == 0 {
+                Job::cpu_bound(500 + pid as u64 * 10)
+            } else {
+                Job::io_bound(1000, 4)
+            };
+            (pid, Process::new(pid, Arc::new(job), Ticks(pid as u64 * 20)))
+        })
+        .collect();
+    std::fs::write(
+        dir.join("workload.json"),
+        result::workload_to_json(&processes)?,
+    )?;
+    for (scheduler, name) in get_schedulers() {
+        let mut os = Os::new(processes.clone(), scheduler, "Archived");
+        os.run();
+        let path = dir.join(format!("{}.json", name));
+        RunResult::new(&os).parameter("name", name).save(&path)?;
+        println!("saved {}", path.display());
+    }
+    Ok(())
+}
+
+/// `run_archive save <dir>` archives the runs of the schedulers,
+/// `run_archive compare <result.json>...` tables archived runs, possibly from different commits
+fn main() {
+    pretty_env_logger::init();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("save") if args.len() == 2 => {
+            if let Err(err) = save(&PathBuf::from(&args[1])) {
+                eprintln!("{}: {}", args[1], err);
+                std::process::exit(1);
+            }
+        }
+        Some("compare") if args.len() > 1 => {
+            let results = args[1..]
+                .iter()
+                .map(|path| {
+                    RunResult::load(path).unwrap_or_else(|err| {
+                        eprintln!("{}: {}", path, err);
+                        std::process::exit(1);
+                    })
+                })
+                .collect::<Vec<_>>();
+            RunResult::parameters_report(&results).to_table().printstd();
+            RunResult::stats_report(&results).to_table().printstd();
+        }
+        _ => usage(),
+    }
+}
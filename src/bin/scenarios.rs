@@ -0,0 +1,14 @@
+use os_learning::scheduling::scenario::textbook;
+
+fn main() {
+    pretty_env_logger::init();
+    for scenario in textbook::scenarios() {
+        let report = scenario.run();
+        println!(
+            "{}: {}",
+            report.os().desc(),
+            if report.is_passed() { "PASSED" } else { "FAILED" }
+        );
+        report.diff_table().printstd();
+    }
+}
@@ -0,0 +1,56 @@
+use os_learning::scheduling::analysis::{InvalidTask, PeriodicTask, TaskSet};
+
+fn get_task_sets() -> Result<Vec<(&'static str, TaskSet)>, InvalidTask> {
+    Ok(vec![
+        (
+            "Within Liu & Layland",
+            TaskSet::new(vec![
+                PeriodicTask::new(1, 4)?,
+                PeriodicTask::new(1, 5)?,
+                PeriodicTask::new(2, 10)?,
+            ]),
+        ),
+        (
+            "Only Response-Time Analysis",
+            TaskSet::new(vec![
+                PeriodicTask::new(1, 4)?,
+                PeriodicTask::new(2, 6)?,
+                PeriodicTask::new(3, 12)?,
+            ]),
+        ),
+        (
+            "EDF Only",
+            TaskSet::new(vec![PeriodicTask::new(2, 5)?, PeriodicTask::new(4, 7)?]),
+        ),
+        (
+            "Constrained Deadlines",
+            TaskSet::new(vec![
+                PeriodicTask::new(3, 10)?,
+                PeriodicTask::new(2, 20)?.with_deadline(4)?,
+            ]),
+        ),
+    ])
+}
+
+/// Cross-checks the analytic tests against simulated runs over one hyperperiod.
+fn main() {
+    pretty_env_logger::init();
+    let task_sets = match get_task_sets() {
+        Ok(task_sets) => task_sets,
+        Err(err) => {
+            eprintln!("invalid task: {}", err);
+            std::process::exit(1);
+        }
+    };
+    for (desc, task_set) in task_sets {
+        match task_set.hyperperiod().and_then(|hyperperiod| {
+            task_set.cross_check_report().map(|report| (hyperperiod, report))
+        }) {
+            Ok((hyperperiod, report)) => {
+                println!("{} (hyperperiod {})", desc, hyperperiod);
+                report.to_table().printstd();
+            }
+            Err(err) => eprintln!("{}: {}", desc, err),
+        }
+    }
+}
@@ -1,28 +1,44 @@
 use std::sync::Arc;
 
+use indexmap::IndexMap;
+
 use os_learning::scheduling::{
-    FirstComeFirstServeScheduler, HighestResponseRatioNextScheduler, Job, LongestJobFirstScheduler,
-    MultilevelFeedbackQueueScheduler, Os, PId, Process, RoundRobinScheduler, Scheduler,
+    ClassPolicy, FirstComeFirstServeScheduler, HighestResponseRatioNextScheduler, Job,
+    LongestJobFirstScheduler, LongestRemainingJobFirstScheduler, MultilevelFeedbackQueueScheduler,
+    MultilevelQueueScheduler, Os, PId, Process, RoundRobinScheduler, Scheduler,
     ShortestJobFirstScheduler, ShortestRemainingJobFirstScheduler,
 };
+use os_learning::scheduling::cache::CacheModel;
+use os_learning::scheduling::power::PowerModel;
+use os_learning::scheduling::replication::{ReplicationReport, Replications};
+use os_learning::scheduling::report::{Csv, Exporter, Html, Markdown};
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::watchdog::Watchdog;
 
-fn run_jobs(cpu_bound_jobs: usize, io_bound_jobs: usize, jobs_desc: &'static str) -> Vec<Os> {
+fn workload(cpu_bound_jobs: usize, io_bound_jobs: usize, seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
     let mut processes = (0..cpu_bound_jobs)
         .map(|_| Arc::new(Job::cpu_bound(1000)))
         .chain((0..io_bound_jobs).map(|_| Arc::new(Job::io_bound(1000, 4))))
         .enumerate()
-        .map(|(id, job)| (id, Process::new(id, job, fastrand::u64(..2000))))
+        .map(|(id, job)| (id, Process::new(id, job, Ticks(rng.u64(..2000)))))
         .collect::<Vec<_>>();
-    fastrand::shuffle(processes.as_mut_slice());
-    let processes = processes
-        .into_iter()
-        .collect::<indexmap::IndexMap<PId, Process>>();
+    rng.shuffle(processes.as_mut_slice());
+    processes.into_iter().collect()
+}
+
+fn run_jobs(cpu_bound_jobs: usize, io_bound_jobs: usize, jobs_desc: &'static str) -> Vec<Os> {
+    let processes = workload(cpu_bound_jobs, io_bound_jobs, fastrand::u64(..));
     get_schedulers()
         .into_iter()
         .map(|scheduler| {
             let processes = processes.clone();
             std::thread::spawn(move || {
-                let mut os = Os::new(processes.clone(), scheduler, jobs_desc);
+                let watchdog = Watchdog::new()
+                    .starvation_threshold(3000)
+                    .stall_limit(10000);
+                let mut os = Os::new(processes.clone(), scheduler, jobs_desc).with_watchdog(watchdog);
                 os.run();
                 os
             })
@@ -31,16 +47,52 @@ fn run_jobs(cpu_bound_jobs: usize, io_bound_jobs: usize, jobs_desc: &'static str
         .collect()
 }
 
+fn replicate(
+    cpu_bound_jobs: usize,
+    io_bound_jobs: usize,
+    jobs_desc: &'static str,
+    replications: usize,
+    with_models: bool,
+) -> ReplicationReport {
+    let replications = Replications::new(jobs_desc, replications, move |seed| {
+        workload(cpu_bound_jobs, io_bound_jobs, seed)
+    });
+    // the single runs have no models, so the replications have none either unless asked
+    let replications = if with_models {
+        replications.configure(|os| {
+            os.with_cache_model(CacheModel::new(4, 3).cold_after(200))
+                .with_power_model(PowerModel::mobile())
+        })
+    } else {
+        replications
+    };
+    (0..get_schedulers().len())
+        .fold(replications, |replications, i| {
+            replications.scheduler(move || get_schedulers().swap_remove(i))
+        })
+        .run()
+}
+
 fn get_schedulers() -> Vec<Box<dyn Scheduler + Send>> {
     vec![
         Box::new(HighestResponseRatioNextScheduler::new()),
         Box::new(ShortestJobFirstScheduler::new()),
         Box::new(ShortestRemainingJobFirstScheduler::new()),
         Box::new(LongestJobFirstScheduler::new()),
-        // Box::new(LongestRemainingJobFirstScheduler::new()),
+        Box::new(LongestRemainingJobFirstScheduler::new()),
         Box::new(FirstComeFirstServeScheduler::new()),
         Box::new(RoundRobinScheduler::new(100)),
         Box::new(MultilevelFeedbackQueueScheduler::new([50, 100])),
+        Box::new(MultilevelQueueScheduler::foreground_background(
+            Box::new(RoundRobinScheduler::new(100)),
+            Box::new(FirstComeFirstServeScheduler::new()),
+            ClassPolicy::StrictPriority,
+        )),
+        Box::new(MultilevelQueueScheduler::foreground_background(
+            Box::new(RoundRobinScheduler::new(100)),
+            Box::new(FirstComeFirstServeScheduler::new()),
+            ClassPolicy::TimeSliced(vec![400, 100]),
+        )),
     ]
 }
 
@@ -49,33 +101,71 @@ fn print_os_list_stats(os_list: &[Os], is_detailed: bool) {
         for os in os_list {
             os.stats_table().printstd();
             os.detailed_process_stats_table().printstd();
+            os.starvation_table().printstd();
         }
     } else {
         Os::os_list_stats_table(os_list).printstd();
+        Os::os_list_starvation_table(os_list).printstd();
+    }
+}
+
+/// `scheduling [markdown|csv|html]` exports the stats instead of printing tables
+fn exporter(format: &str) -> Option<Box<dyn Exporter>> {
+    match format {
+        "markdown" | "md" => Some(Box::new(Markdown)),
+        "csv" => Some(Box::new(Csv)),
+        "html" => Some(Box::new(Html::new("Scheduling Comparison"))),
+        _ => None,
+    }
+}
+
+/// `scheduling replicate [N] [--models]` runs N seeded replications, 30 by default,
+/// `--models` adds a cache model and a mobile power model to every run
+fn main_replicate(replications: usize, with_models: bool) {
+    let reports = vec![
+        std::thread::spawn(move || replicate(8, 2, "CPU Bound", replications, with_models)),
+        std::thread::spawn(move || replicate(2, 8, "I/O Bound", replications, with_models)),
+        std::thread::spawn(move || replicate(5, 5, "Average", replications, with_models)),
+    ];
+    for report in reports {
+        let report = report.join().expect("replications failed");
+        report.summary_report().to_table().printstd();
+        report.significance_report().to_table().printstd();
     }
 }
 
 fn main() {
     pretty_env_logger::init();
+    let mut args = std::env::args().skip(1);
+    let format = args.next();
+    if format.as_deref() == Some("replicate") {
+        let args = args.collect::<Vec<_>>();
+        let with_models = args.iter().any(|arg| arg == "--models");
+        let replications = args
+            .iter()
+            .find_map(|n| n.parse().ok())
+            .unwrap_or(30);
+        main_replicate(replications, with_models);
+        return;
+    }
+    let exporter = format.and_then(|format| exporter(&format));
     let cpu_bound_test = std::thread::spawn(|| run_jobs(8, 2, "CPU Bound"));
     let io_bound_test = std::thread::spawn(|| run_jobs(2, 8, "I/O Bound"));
     let average_test = std::thread::spawn(|| run_jobs(5, 5, "Average"));
-    print_os_list_stats(
-        cpu_bound_test
-            .join()
-            .expect("cpu bound test failed")
-            .as_slice(),
-        false,
-    );
-    print_os_list_stats(
-        io_bound_test
-            .join()
-            .expect("io bound test failed")
-            .as_slice(),
-        false,
-    );
-    print_os_list_stats(
-        average_test.join().expect("average test failed").as_slice(),
-        false,
-    );
+    let os_lists = [
+        cpu_bound_test.join().expect("cpu bound test failed"),
+        io_bound_test.join().expect("io bound test failed"),
+        average_test.join().expect("average test failed"),
+    ];
+    if let Some(exporter) = exporter {
+        let reports = os_lists
+            .iter()
+            .map(|os_list| Os::os_list_stats_report(os_list))
+            .collect::<Vec<_>>();
+        print!("{}", exporter.export(&reports));
+    } else {
+        for os_list in os_lists.iter() {
+            print_os_list_stats(os_list, false);
+        }
+    }
 }
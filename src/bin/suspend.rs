@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::medium_term::{MediumTermScheduler, SwapPolicy};
+use os_learning::scheduling::report::Report;
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{Os, PId, Process, RoundRobinScheduler};
+
+/// I/O-heavy processes of different sizes, arriving in the first 100 ticks
+fn workload(seed: u64) -> IndexMap<PId, Process> {
+    let rng = fastrand::Rng::new();
+    rng.seed(seed);
+    (0..8)
+        .map(|pid| {
+            let job = JobBuilder::new()
+                .seed(rng.u64(..))
+                .random_bursts(4, 5..30, 20..120)
+                .build();
+            let process =
+                Process::new(pid, Arc::new(job), Ticks(rng.u64(..100))).with_memory(rng.u64(1..=4));
+            (pid, process)
+        })
+        .collect()
+}
+
+fn get_medium_terms() -> Vec<(&'static str, Option<MediumTermScheduler>)> {
+    vec![
+        ("Unlimited", None),
+        (
+            "3 Processes, Latest Wake-up",
+            Some(MediumTermScheduler::new(SwapPolicy::LatestWakeUp).multiprogramming_limit(3)),
+        ),
+        (
+            "8 Units, Latest Wake-up",
+            Some(MediumTermScheduler::new(SwapPolicy::LatestWakeUp).memory_limit(8)),
+        ),
+        (
+            "8 Units, Largest",
+            Some(MediumTermScheduler::new(SwapPolicy::Largest).memory_limit(8)),
+        ),
+    ]
+}
+
+/// `suspend [seed]` compares multiprogramming and memory limits, swapping whole processes out
+fn main() {
+    pretty_env_logger::init();
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+    let processes = workload(seed);
+    let mut os_list = vec![];
+    for (desc, medium_term) in get_medium_terms() {
+        let scheduler = Box::new(RoundRobinScheduler::new(10));
+        let mut os = Os::new(processes.clone(), scheduler, desc);
+        if let Some(medium_term) = medium_term {
+            os = os.with_medium_term(medium_term);
+        }
+        os.run();
+        os_list.push(os);
+    }
+    println!("seed: {}", seed);
+    Os::os_list_stats_table(&os_list).printstd();
+    for os in &os_list[1..] {
+        println!("{}", os.jobs_desc());
+        let mut report = Report::new(&["Process", "State", "Start", "End"]);
+        for suspension in os.suspensions() {
+            report.add_row(vec![
+                format!("P{}", suspension.pid),
+                format!("{:?}", suspension.state),
+                suspension.start.to_string(),
+                suspension.end.to_string(),
+            ]);
+        }
+        report.to_table().printstd();
+    }
+}
@@ -0,0 +1,33 @@
+use os_learning::scheduling::job::JobBuilder;
+use os_learning::scheduling::thread::{self, MultithreadedProcess, ThreadModel};
+use os_learning::scheduling::timer::Ticks;
+use os_learning::scheduling::{Os, RoundRobinScheduler};
+
+fn processes(model: ThreadModel) -> Vec<MultithreadedProcess> {
+    let server = (0..4).fold(MultithreadedProcess::new(0, Ticks::ZERO, model), |process, _| {
+        process.thread(JobBuilder::new().ratio(600, 20, 4).build())
+    });
+    let batch = (1..3).map(|id| {
+        MultithreadedProcess::new(id, Ticks(50), model).thread(JobBuilder::new().cpu(800).build())
+    });
+    std::iter::once(server).chain(batch).collect()
+}
+
+/// Runs the same I/O bound server with 4 threads next to CPU bound batch processes
+/// under both thread models.
+fn main() {
+    pretty_env_logger::init();
+    for &model in [ThreadModel::KernelLevel, ThreadModel::UserLevel].iter() {
+        let processes = processes(model);
+        let mut os = Os::new(
+            thread::tasks(&processes),
+            Box::new(RoundRobinScheduler::new(20)),
+            model.desc(),
+        );
+        os.run();
+        os.stats_table().printstd();
+        thread::process_stats_report(&os, &processes)
+            .to_table()
+            .printstd();
+    }
+}
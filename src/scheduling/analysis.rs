@@ -0,0 +1,488 @@
+//! Schedulability Analysis
+//!
+//! Analytic tests for a set of periodic tasks released synchronously at time 0,
+//! which can be cross-checked against a simulated run of [`Os`] over one hyperperiod.
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::scheduling::report::Report;
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Job, Os, PId, PeriodicPolicy, PeriodicScheduler, Process};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PeriodicTask {
+    /// worst-case execution time
+    wcet: u64,
+    period: u64,
+    /// relative deadline, no longer than the period
+    deadline: u64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InvalidTask {
+    ZeroPeriod,
+    ZeroDeadline,
+    DeadlineLongerThanPeriod { deadline: u64, period: u64 },
+}
+
+impl fmt::Display for InvalidTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidTask::ZeroPeriod => write!(f, "period must be positive"),
+            InvalidTask::ZeroDeadline => write!(f, "deadline must be positive"),
+            InvalidTask::DeadlineLongerThanPeriod { deadline, period } => {
+                write!(f, "deadline {} longer than period {}", deadline, period)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidTask {}
+
+/// The least common multiple of the periods does not fit in a `u64`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HyperperiodOverflow;
+
+impl fmt::Display for HyperperiodOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hyperperiod overflows")
+    }
+}
+
+impl std::error::Error for HyperperiodOverflow {}
+
+impl PeriodicTask {
+    /// A task with an implicit deadline equal to its period.
+    pub fn new(wcet: u64, period: u64) -> Result<Self, InvalidTask> {
+        if period == 0 {
+            return Err(InvalidTask::ZeroPeriod);
+        }
+        Ok(Self {
+            wcet,
+            period,
+            deadline: period,
+        })
+    }
+    pub fn with_deadline(mut self, deadline: u64) -> Result<Self, InvalidTask> {
+        if deadline == 0 {
+            return Err(InvalidTask::ZeroDeadline);
+        }
+        if deadline > self.period {
+            return Err(InvalidTask::DeadlineLongerThanPeriod {
+                deadline,
+                period: self.period,
+            });
+        }
+        self.deadline = deadline;
+        Ok(self)
+    }
+    pub fn wcet(&self) -> u64 {
+        self.wcet
+    }
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+    pub fn utilization(&self) -> f64 {
+        self.wcet as f64 / self.period as f64
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskSet {
+    pub tasks: Vec<PeriodicTask>,
+}
+
+impl TaskSet {
+    pub fn new(tasks: Vec<PeriodicTask>) -> Self {
+        Self { tasks }
+    }
+    pub fn task(mut self, task: PeriodicTask) -> Self {
+        self.tasks.push(task);
+        self
+    }
+    pub fn utilization(&self) -> f64 {
+        self.tasks.iter().map(PeriodicTask::utilization).sum()
+    }
+    pub fn hyperperiod(&self) -> Result<u64, HyperperiodOverflow> {
+        self.tasks.iter().try_fold(1u64, |lcm, task| {
+            (lcm / gcd(lcm, task.period))
+                .checked_mul(task.period)
+                .ok_or(HyperperiodOverflow)
+        })
+    }
+    fn has_implicit_deadlines(&self) -> bool {
+        self.tasks.iter().all(|task| task.deadline == task.period)
+    }
+    /// `n(2^(1/n) - 1)`
+    pub fn liu_layland_bound(&self) -> f64 {
+        let n = self.tasks.len() as f64;
+        if n == 0.0 {
+            return 1.0;
+        }
+        n * (2f64.powf(1.0 / n) - 1.0)
+    }
+    /// Sufficient test for RM with implicit deadlines: `U <= n(2^(1/n) - 1)`.
+    pub fn passes_liu_layland(&self) -> bool {
+        self.has_implicit_deadlines() && self.utilization() <= self.liu_layland_bound()
+    }
+    /// Sufficient test for RM with implicit deadlines, tighter than Liu & Layland:
+    /// `Π(U_i + 1) <= 2`.
+    pub fn passes_hyperbolic_bound(&self) -> bool {
+        self.has_implicit_deadlines()
+            && self
+                .tasks
+                .iter()
+                .map(|task| task.utilization() + 1.0)
+                .product::<f64>()
+                <= 2.0
+    }
+    /// Task indexes from the highest priority to the lowest, ties are broken by index.
+    pub fn priority_order(&self, policy: PeriodicPolicy) -> Vec<usize> {
+        let mut order = (0..self.tasks.len()).collect::<Vec<_>>();
+        match policy {
+            PeriodicPolicy::RateMonotonic => order.sort_by_key(|&i| self.tasks[i].period),
+            PeriodicPolicy::DeadlineMonotonic => order.sort_by_key(|&i| self.tasks[i].deadline),
+            PeriodicPolicy::EarliestDeadlineFirst => {}
+        }
+        order
+    }
+    /// Exact response-time analysis for fixed priorities:
+    /// `R = C_i + Σ_(j in hp(i)) ceil(R / T_j) * C_j`, iterated until it converges.
+    ///
+    /// returns: worst-case response time of every task, `None` once it exceeds the deadline
+    pub fn response_times(&self, policy: PeriodicPolicy) -> Vec<Option<u64>> {
+        assert!(
+            policy != PeriodicPolicy::EarliestDeadlineFirst,
+            "response-time analysis needs fixed priorities"
+        );
+        let order = self.priority_order(policy);
+        let mut response_times = vec![None; self.tasks.len()];
+        for (rank, &i) in order.iter().enumerate() {
+            let task = &self.tasks[i];
+            let higher = &order[..rank];
+            let mut response_time = task.wcet;
+            loop {
+                let next = task.wcet
+                    + higher
+                        .iter()
+                        .map(|&j| {
+                            let hp = &self.tasks[j];
+                            (response_time + hp.period - 1) / hp.period * hp.wcet
+                        })
+                        .sum::<u64>();
+                if next > task.deadline {
+                    break;
+                }
+                if next == response_time {
+                    response_times[i] = Some(response_time);
+                    break;
+                }
+                response_time = next;
+            }
+        }
+        response_times
+    }
+    pub fn passes_response_time_analysis(&self, policy: PeriodicPolicy) -> bool {
+        self.response_times(policy).iter().all(Option::is_some)
+    }
+    /// Processor demand of the jobs with both release and deadline in `[0, length]`:
+    /// `Σ max(0, floor((L - D_i) / T_i) + 1) * C_i`.
+    pub fn demand(&self, length: u64) -> u64 {
+        self.tasks
+            .iter()
+            .filter(|task| task.deadline <= length)
+            .map(|task| ((length - task.deadline) / task.period + 1) * task.wcet)
+            .sum()
+    }
+    /// Exact test for EDF, the demand never exceeds the length at any absolute deadline
+    /// up to the hyperperiod plus the longest deadline.
+    pub fn passes_processor_demand(&self) -> Result<bool, HyperperiodOverflow> {
+        if self.utilization() > 1.0 {
+            return Ok(false);
+        }
+        let longest_deadline = self.tasks.iter().map(|t| t.deadline).max().unwrap_or(0);
+        let horizon = self
+            .hyperperiod()?
+            .checked_add(longest_deadline)
+            .ok_or(HyperperiodOverflow)?;
+        Ok(self.tasks.iter().all(|task| {
+            (0..)
+                .map(|k| k * task.period + task.deadline)
+                .take_while(|&length| length <= horizon)
+                .all(|length| self.demand(length) <= length)
+        }))
+    }
+    pub fn is_schedulable(&self, policy: PeriodicPolicy) -> Result<bool, HyperperiodOverflow> {
+        match policy {
+            PeriodicPolicy::EarliestDeadlineFirst => self.passes_processor_demand(),
+            _ => Ok(self.passes_response_time_analysis(policy)),
+        }
+    }
+    /// Runs every job released in the first hyperperiod through [`Os`].
+    pub fn simulate(&self, policy: PeriodicPolicy) -> Result<Simulation, HyperperiodOverflow> {
+        let hyperperiod = self.hyperperiod()?;
+        let order = self.priority_order(policy);
+        let mut jobs = vec![];
+        let mut processes = IndexMap::new();
+        let mut keys = HashMap::new();
+        for &task_index in &order {
+            let task = &self.tasks[task_index];
+            let job = Arc::new(Job::cpu_bound(task.wcet));
+            for release in (0..hyperperiod).step_by(task.period as usize) {
+                let pid = jobs.len();
+                let deadline = release + task.deadline;
+                // ties by task index, as in the priority order
+                let key = match policy {
+                    PeriodicPolicy::RateMonotonic => (task.period, task_index),
+                    PeriodicPolicy::DeadlineMonotonic => (task.deadline, task_index),
+                    PeriodicPolicy::EarliestDeadlineFirst => (deadline, task_index),
+                };
+                keys.insert(pid, key);
+                processes.insert(pid, Process::new(pid, job.clone(), Ticks(release)));
+                jobs.push(PeriodicJob {
+                    pid,
+                    task: task_index,
+                    release,
+                    deadline,
+                });
+            }
+        }
+        let scheduler = Box::new(PeriodicScheduler::new(policy, keys));
+        let mut os = Os::new(processes, scheduler, "Periodic Tasks");
+        os.run();
+        Ok(Simulation {
+            tasks: self.tasks.len(),
+            jobs,
+            os,
+        })
+    }
+    /// Analytic verdicts next to the simulated ones.
+    pub fn cross_check_report(&self) -> Result<Report, HyperperiodOverflow> {
+        let mut report = Report::new(&["Test", "Policy", "Analytic", "Simulated"]);
+        let verdict = |passed: bool| {
+            if passed {
+                "Schedulable"
+            } else {
+                "Not Schedulable"
+            }
+        };
+        let sufficient = |passed: bool| {
+            if passed {
+                "Schedulable"
+            } else {
+                "Inconclusive"
+            }
+        };
+        let rm = self.simulate(PeriodicPolicy::RateMonotonic)?;
+        let dm = self.simulate(PeriodicPolicy::DeadlineMonotonic)?;
+        let edf = self.simulate(PeriodicPolicy::EarliestDeadlineFirst)?;
+        let rows = vec![
+            (
+                format!(
+                    "Liu & Layland (U={:.3}, Bound={:.3})",
+                    self.utilization(),
+                    self.liu_layland_bound()
+                ),
+                "RM",
+                sufficient(self.passes_liu_layland()),
+                &rm,
+            ),
+            (
+                "Hyperbolic Bound".to_string(),
+                "RM",
+                sufficient(self.passes_hyperbolic_bound()),
+                &rm,
+            ),
+            (
+                "Response-Time Analysis".to_string(),
+                "RM",
+                verdict(self.passes_response_time_analysis(PeriodicPolicy::RateMonotonic)),
+                &rm,
+            ),
+            (
+                "Response-Time Analysis".to_string(),
+                "DM",
+                verdict(self.passes_response_time_analysis(PeriodicPolicy::DeadlineMonotonic)),
+                &dm,
+            ),
+            (
+                "Processor Demand".to_string(),
+                "EDF",
+                verdict(self.passes_processor_demand()?),
+                &edf,
+            ),
+        ];
+        for (test, policy, analytic, simulation) in rows {
+            report.add_row(vec![
+                test,
+                policy.to_string(),
+                analytic.to_string(),
+                verdict(simulation.deadline_misses().is_empty()).to_string(),
+            ]);
+        }
+        Ok(report)
+    }
+}
+
+/// A job released by a periodic task.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PeriodicJob {
+    pub pid: PId,
+    pub task: usize,
+    pub release: u64,
+    /// absolute deadline
+    pub deadline: u64,
+}
+
+pub struct Simulation {
+    tasks: usize,
+    jobs: Vec<PeriodicJob>,
+    os: Os,
+}
+
+impl Simulation {
+    pub fn os(&self) -> &Os {
+        &self.os
+    }
+    pub fn jobs(&self) -> &[PeriodicJob] {
+        self.jobs.as_slice()
+    }
+    fn completion_time(&self, job: &PeriodicJob) -> u64 {
+        self.os
+            .get_process(job.pid)
+            .map_or(u64::MAX, |p| p.completion_time().0)
+    }
+    /// Jobs completed after their absolute deadlines.
+    pub fn deadline_misses(&self) -> Vec<PeriodicJob> {
+        self.jobs
+            .iter()
+            .filter(|job| self.completion_time(job) > job.deadline)
+            .copied()
+            .collect()
+    }
+    /// Longest observed response time of every task.
+    pub fn worst_response_times(&self) -> Vec<u64> {
+        let mut response_times = vec![0; self.tasks];
+        for job in self.jobs.iter() {
+            let response_time = self.completion_time(job) - job.release;
+            response_times[job.task] = response_times[job.task].max(response_time);
+        }
+        response_times
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_time_analysis_is_exact() {
+        let task_set = TaskSet::new(vec![
+            PeriodicTask::new(3, 12).unwrap(),
+            PeriodicTask::new(2, 6).unwrap(),
+            PeriodicTask::new(1, 4).unwrap(),
+        ]);
+        assert!(!task_set.passes_liu_layland());
+        assert!(!task_set.passes_hyperbolic_bound());
+        let response_times = task_set.response_times(PeriodicPolicy::RateMonotonic);
+        assert_eq!(response_times, vec![Some(10), Some(3), Some(1)]);
+        let simulation = task_set.simulate(PeriodicPolicy::RateMonotonic).unwrap();
+        assert!(simulation.deadline_misses().is_empty());
+        assert_eq!(simulation.worst_response_times(), vec![10, 3, 1]);
+    }
+
+    #[test]
+    fn rejects_invalid_tasks() {
+        assert_eq!(PeriodicTask::new(1, 0), Err(InvalidTask::ZeroPeriod));
+        assert_eq!(
+            PeriodicTask::new(1, 4).unwrap().with_deadline(5),
+            Err(InvalidTask::DeadlineLongerThanPeriod {
+                deadline: 5,
+                period: 4
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_hyperperiods() {
+        let task_set = TaskSet::new(
+            [1_000_003, 1_000_033, 1_000_037, 1_000_039]
+                .iter()
+                .map(|&period| PeriodicTask::new(1, period).unwrap())
+                .collect(),
+        );
+        assert_eq!(task_set.hyperperiod(), Err(HyperperiodOverflow));
+        assert!(task_set.simulate(PeriodicPolicy::RateMonotonic).is_err());
+        assert_eq!(
+            task_set.is_schedulable(PeriodicPolicy::EarliestDeadlineFirst),
+            Err(HyperperiodOverflow)
+        );
+        assert_eq!(
+            task_set.is_schedulable(PeriodicPolicy::RateMonotonic),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn edf_schedules_what_rm_misses() {
+        let task_set = TaskSet::new(vec![
+            PeriodicTask::new(2, 5).unwrap(),
+            PeriodicTask::new(4, 7).unwrap(),
+        ]);
+        assert_eq!(task_set.hyperperiod(), Ok(35));
+        assert_eq!(task_set.is_schedulable(PeriodicPolicy::RateMonotonic), Ok(false));
+        assert!(!task_set
+            .simulate(PeriodicPolicy::RateMonotonic)
+            .unwrap()
+            .deadline_misses()
+            .is_empty());
+        assert_eq!(
+            task_set.is_schedulable(PeriodicPolicy::EarliestDeadlineFirst),
+            Ok(true)
+        );
+        assert!(task_set
+            .simulate(PeriodicPolicy::EarliestDeadlineFirst)
+            .unwrap()
+            .deadline_misses()
+            .is_empty());
+    }
+
+    #[test]
+    fn deadline_monotonic_with_constrained_deadlines() {
+        let task_set = TaskSet::new(vec![
+            PeriodicTask::new(3, 10).unwrap(),
+            PeriodicTask::new(2, 20).unwrap().with_deadline(4).unwrap(),
+        ]);
+        assert_eq!(task_set.is_schedulable(PeriodicPolicy::RateMonotonic), Ok(false));
+        assert_eq!(
+            task_set.response_times(PeriodicPolicy::DeadlineMonotonic),
+            vec![Some(5), Some(2)]
+        );
+        for &policy in [
+            PeriodicPolicy::RateMonotonic,
+            PeriodicPolicy::DeadlineMonotonic,
+            PeriodicPolicy::EarliestDeadlineFirst,
+        ]
+        .iter()
+        {
+            assert_eq!(
+                task_set.is_schedulable(policy),
+                Ok(task_set.simulate(policy).unwrap().deadline_misses().is_empty())
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Cache Warmth
+//!
+//! A process resumed after other processes have run finds its working set evicted,
+//! so its first ticks on the CPU refill the cache and make no progress.
+use crate::scheduling::timer::Ticks;
+
+/// How many warm-up ticks a process pays when it is dispatched.
+///
+/// The cache is colder the more distinct processes ran since the process last ran,
+/// and the longer it has been off the CPU.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheModel {
+    /// warm-up ticks of a fully cold cache
+    pub cold_penalty: u64,
+    /// distinct processes which evict the whole cache
+    pub capacity: usize,
+    /// ticks off the CPU after which the cache is cold anyway
+    pub cold_after: Option<u64>,
+}
+
+impl CacheModel {
+    pub fn new(cold_penalty: u64, capacity: usize) -> Self {
+        Self {
+            cold_penalty,
+            capacity: capacity.max(1),
+            cold_after: None,
+        }
+    }
+    pub fn cold_after(mut self, cold_after: u64) -> Self {
+        self.cold_after = Some(cold_after.max(1));
+        self
+    }
+    /// `last_ran_at` is `None` for the first dispatch, which always starts cold.
+    pub fn penalty(
+        &self,
+        processes_run_since: usize,
+        last_ran_at: Option<Ticks>,
+        clock: Ticks,
+    ) -> u64 {
+        let last_ran_at = match last_ran_at {
+            Some(last_ran_at) => last_ran_at,
+            None => return self.cold_penalty,
+        };
+        let by_eviction = self.cold_penalty * processes_run_since.min(self.capacity) as u64
+            / self.capacity as u64;
+        let by_decay = self.cold_after.map_or(0, |cold_after| {
+            let idle = clock.since(last_ran_at).min(cold_after);
+            self.cold_penalty * idle / cold_after
+        });
+        by_eviction.max(by_decay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::FirstComeFirstServeScheduler;
+
+    #[test]
+    fn colder_with_more_processes_in_between() {
+        let model = CacheModel::new(8, 4).cold_after(100);
+        assert_eq!(model.penalty(0, None, Ticks(0)), 8);
+        assert_eq!(model.penalty(0, Some(Ticks(10)), Ticks(10)), 0);
+        assert_eq!(model.penalty(1, Some(Ticks(10)), Ticks(12)), 2);
+        assert_eq!(model.penalty(9, Some(Ticks(10)), Ticks(12)), 8);
+        assert_eq!(model.penalty(1, Some(Ticks(10)), Ticks(60)), 4);
+    }
+
+    #[test]
+    fn warm_up_is_not_waiting() {
+        let os = Scenario::new("cache", Box::new(FirstComeFirstServeScheduler::new()))
+            .process(0, 0, 5)
+            .configure(|os| os.with_cache_model(CacheModel::new(8, 4)))
+            .run()
+            .into_os();
+        let process = os.get_process(0).unwrap();
+        assert_eq!(process.completion_time(), Ticks(13));
+        assert_eq!((process.warm_up_time(), process.waiting_time()), (8, 0));
+    }
+}
@@ -0,0 +1,166 @@
+//! Scheduling Decision Explanations
+//!
+//! A scheduler may explain each dispatch: the ready processes it chose from, each with the key
+//! it compared them by, and the rule which made the winner. See [`Os::with_explanations`].
+//!
+//! [`Os::with_explanations`]: crate::scheduling::Os::with_explanations
+use std::fmt;
+
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::PId;
+
+/// What a scheduler compares the ready processes by.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Key {
+    /// position in a FIFO ready queue
+    Position(usize),
+    BurstTime(u64),
+    RemainingTime(u64),
+    /// `(Waiting Time + Burst time) / Burst time`
+    ResponseRatio {
+        waiting_time: u64,
+        burst_time: u64,
+    },
+    /// level of the queue, 0 the highest, and position in it
+    QueueLevel {
+        level: usize,
+        position: usize,
+    },
+    /// the lower the higher priority
+    Priority(usize),
+    Period(u64),
+    RelativeDeadline(u64),
+    AbsoluteDeadline(u64),
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Key::Position(position) => write!(f, "position {}", position),
+            Key::BurstTime(burst_time) => write!(f, "burst time {}", burst_time),
+            Key::RemainingTime(remaining_time) => write!(f, "remaining time {}", remaining_time),
+            Key::ResponseRatio {
+                waiting_time,
+                burst_time,
+            } => write!(
+                f,
+                "response ratio ({} + {}) / {} = {:.2}",
+                waiting_time,
+                burst_time,
+                burst_time,
+                (waiting_time + burst_time) as f64 / burst_time.max(1) as f64
+            ),
+            Key::QueueLevel { level, position } => {
+                write!(f, "queue {} position {}", level, position)
+            }
+            Key::Priority(priority) => write!(f, "priority {}", priority),
+            Key::Period(period) => write!(f, "period {}", period),
+            Key::RelativeDeadline(deadline) => write!(f, "relative deadline {}", deadline),
+            Key::AbsoluteDeadline(deadline) => write!(f, "absolute deadline {}", deadline),
+        }
+    }
+}
+
+/// Why a process was dispatched.
+///
+/// The scheduler gives the candidates and the rule before it picks,
+/// and the [`Os`](crate::scheduling::Os) fills in the rest when the switch happens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub clock: Ticks,
+    /// the process which held the CPU before the switch
+    pub previous: Option<PId>,
+    /// whether `previous` was still running, rather than blocked or completed
+    pub is_preemption: bool,
+    /// ready processes with their keys, the one the rule favors first,
+    /// then the running process if it is preempted
+    pub candidates: Vec<(PId, Key)>,
+    pub rule: String,
+    /// `None` until the switch happens
+    pub chosen: Option<PId>,
+}
+
+impl Explanation {
+    pub fn new(rule: impl Into<String>, candidates: Vec<(PId, Key)>) -> Self {
+        Self {
+            clock: Ticks::ZERO,
+            previous: None,
+            is_preemption: false,
+            candidates,
+            rule: rule.into(),
+            chosen: None,
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: ", self.clock)?;
+        match (self.chosen, self.previous) {
+            (None, _) => write!(f, "nothing runs yet")?,
+            (Some(chosen), Some(previous)) if chosen == previous => {
+                write!(f, "P{} keeps running", chosen)?
+            }
+            (Some(chosen), Some(previous)) if self.is_preemption => {
+                write!(f, "P{} preempts P{}", chosen, previous)?
+            }
+            (Some(chosen), Some(previous)) => write!(f, "P{} runs after P{}", chosen, previous)?,
+            (Some(chosen), None) => write!(f, "P{} runs", chosen)?,
+        }
+        write!(f, ", by {}", self.rule)?;
+        for (pid, key) in &self.candidates {
+            let mark = if Some(*pid) == self.chosen {
+                "->"
+            } else {
+                "  "
+            };
+            write!(f, "\n  {} P{:<3} {}", mark, pid, key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::{Os, ShortestRemainingJobFirstScheduler};
+
+    #[test]
+    fn explains_preemption_by_remaining_time() {
+        let scheduler = Box::new(ShortestRemainingJobFirstScheduler::new());
+        let os = Scenario::new("explain", scheduler)
+            .process(0, 0, 10)
+            .process(1, 2, 3)
+            .process(2, 2, 5)
+            .configure(Os::with_explanations)
+            .run()
+            .into_os();
+        let explanations = os.explanations();
+        let summary = explanations
+            .iter()
+            .map(|e| (e.clock, e.previous, e.chosen, e.is_preemption))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (Ticks(0), None, Some(0), false),
+                (Ticks(2), Some(0), Some(1), true),
+                (Ticks(5), Some(1), Some(2), false),
+                (Ticks(10), Some(2), Some(0), false),
+            ]
+        );
+        // P2 arrives at 2 as well, but after P1 has preempted P0
+        assert_eq!(
+            explanations[1].candidates,
+            vec![(1, Key::RemainingTime(3)), (0, Key::RemainingTime(8))]
+        );
+        assert_eq!(
+            explanations[2].candidates,
+            vec![(2, Key::RemainingTime(5)), (0, Key::RemainingTime(8))]
+        );
+        assert!(os
+            .narrative()
+            .contains("at 2: P1 preempts P0, by shortest remaining time"));
+    }
+}
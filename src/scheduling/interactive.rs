@@ -0,0 +1,105 @@
+//! Interactive Jobs
+//!
+//! An interactive job answers its user with a CPU burst, then waits while the user thinks
+//! about the answer before making the next request. What the user feels is the response time
+//! of each interaction, from the request until the CPU burst answering it finished.
+use crate::scheduling::report::Report;
+use crate::scheduling::Os;
+
+/// Response times of all the interactions in a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionStats {
+    pub interactions: usize,
+    pub average_response_time: u64,
+    /// 95th percentile
+    pub tail_response_time: u64,
+    pub max_response_time: u64,
+    /// fraction of interactions answered within the SLA
+    pub within_sla: f64,
+}
+
+impl InteractionStats {
+    /// `sla` is the longest acceptable response time.
+    pub fn new(os: &Os, sla: u64) -> Self {
+        let mut response_times = os
+            .processes()
+            .flat_map(|p| p.response_times().iter().copied())
+            .collect::<Vec<_>>();
+        response_times.sort_unstable();
+        let interactions = response_times.len();
+        let percentile = |percent: usize| {
+            interactions
+                .checked_sub(1)
+                .map_or(0, |last| response_times[last * percent / 100])
+        };
+        let within_sla = response_times.iter().filter(|&&t| t <= sla).count();
+        Self {
+            interactions,
+            average_response_time: response_times
+                .iter()
+                .sum::<u64>()
+                .checked_div(interactions as u64)
+                .unwrap_or(0),
+            tail_response_time: percentile(95),
+            max_response_time: percentile(100),
+            within_sla: within_sla as f64 / interactions.max(1) as f64,
+        }
+    }
+    pub fn report(os_list: &[Os], sla: u64) -> Report {
+        let within_sla = format!("Within SLA {}", sla);
+        let titles = [
+            "Job",
+            "Scheduler",
+            "Interactions",
+            "Ave Response",
+            "95th Response",
+            "Max Response",
+            within_sla.as_str(),
+        ];
+        let mut report = Report::new(&titles).align_right(1);
+        for os in os_list {
+            let stats = Self::new(os, sla);
+            report.add_row(vec![
+                os.jobs_desc().to_string(),
+                os.scheduler_desc().to_string(),
+                stats.interactions.to_string(),
+                stats.average_response_time.to_string(),
+                stats.tail_response_time.to_string(),
+                stats.max_response_time.to_string(),
+                format!("{:.0}%", stats.within_sla * 100.0),
+            ]);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::timer::Ticks;
+    use crate::scheduling::{FirstComeFirstServeScheduler, Process};
+
+    #[test]
+    fn batch_job_delays_the_first_interaction() {
+        let batch = JobBuilder::new().cpu(10).build();
+        let interactive = JobBuilder::new().cpu(2).think(3).cpu(2).build();
+        assert!(interactive.is_interactive());
+        let processes = vec![
+            (0, Process::new(0, Arc::new(batch), Ticks::ZERO)),
+            (1, Process::new(1, Arc::new(interactive), Ticks::ZERO)),
+        ]
+        .into_iter()
+        .collect();
+        let scheduler = Box::new(FirstComeFirstServeScheduler::new());
+        let mut os = Os::new(processes, scheduler, "interactive");
+        os.run();
+        assert_eq!(os.get_process(1).unwrap().response_times(), &[12, 2]);
+        assert!(os.get_process(0).unwrap().response_times().is_empty());
+        let stats = InteractionStats::new(&os, 5);
+        assert_eq!((stats.interactions, stats.max_response_time), (2, 12));
+        assert!((stats.within_sla - 0.5).abs() < 1e-9);
+    }
+}
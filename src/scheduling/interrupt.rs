@@ -0,0 +1,85 @@
+//! Interrupts
+//!
+//! Timer ticks and I/O completions interrupt the CPU, and their handlers run in the kernel
+//! before the CPU returns to the running process. The handler ticks are system time,
+//! charged to the interrupted process if any, while its own bursts are user time.
+use crate::scheduling::timer::Ticks;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Interrupt {
+    Timer,
+    IoCompletion,
+}
+
+/// Costs of the interrupt handlers, in ticks.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterruptModel {
+    /// ticks between two timer interrupts
+    pub timer_interval: Option<u64>,
+    pub timer_handler: u64,
+    pub io_handler: u64,
+}
+
+impl InterruptModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Raises a timer interrupt every `interval` ticks, `interval` is at least 1.
+    pub fn timer(mut self, interval: u64, handler: u64) -> Self {
+        self.timer_interval = Some(interval.max(1));
+        self.timer_handler = handler;
+        self
+    }
+    pub fn io_completion(mut self, handler: u64) -> Self {
+        self.io_handler = handler;
+        self
+    }
+    pub fn handler(&self, interrupt: Interrupt) -> u64 {
+        match interrupt {
+            Interrupt::Timer => self.timer_handler,
+            Interrupt::IoCompletion => self.io_handler,
+        }
+    }
+    /// Whether the timer fires at the start of the tick at `clock`.
+    pub fn is_timer_due(&self, clock: Ticks) -> bool {
+        self.timer_interval
+            .map_or(false, |interval| clock.0 > 0 && clock.0 % interval == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::{FirstComeFirstServeScheduler, Os};
+
+    fn run(job: JobBuilder, interrupt_model: InterruptModel) -> Os {
+        Scenario::new("interrupts", Box::new(FirstComeFirstServeScheduler::new()))
+            .process_with_job(0, 0, job.build())
+            .configure(move |os| os.with_interrupts(interrupt_model))
+            .run()
+            .into_os()
+    }
+
+    #[test]
+    fn timer_handler_is_charged_to_the_running_process() {
+        let os = run(JobBuilder::new().cpu(10), InterruptModel::new().timer(4, 1));
+        let process = os.get_process(0).unwrap();
+        assert_eq!(process.completion_time(), Ticks(12));
+        assert_eq!((process.burst_time(), process.system_time()), (10, 2));
+        assert_eq!(process.waiting_time(), 0);
+        assert_eq!(os.system_time(), 2);
+    }
+
+    #[test]
+    fn io_handler_delays_the_woken_process() {
+        let job = JobBuilder::new().cpu(3).io(5).cpu(3);
+        let os = run(job, InterruptModel::new().io_completion(2));
+        let process = os.get_process(0).unwrap();
+        assert_eq!(process.completion_time(), Ticks(13));
+        assert_eq!(process.system_time(), 2);
+        assert_eq!((os.interrupt_count(), os.system_time()), (1, 2));
+    }
+}
@@ -1,45 +1,44 @@
+use std::ops::Range;
+
 use super::statement::Statement;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Job {
     pub statements: Vec<Statement>,
     pub total_duration: u64,
     pub total_cpu_duration: u64,
     pub total_io_duration: u64,
-    pub is_io_bound: bool,
+    pub total_think_duration: u64,
 }
 
 impl Job {
     pub fn cpu_bound(total_duration: u64) -> Self {
-        Self {
-            statements: vec![Statement::cpu_bound(total_duration)],
-            total_duration,
-            total_cpu_duration: total_duration,
-            total_io_duration: 0,
-            is_io_bound: false,
-        }
+        JobBuilder::new().cpu(total_duration).build()
     }
-    /// ios: I/O statements count
+    /// 20% CPU and 80% I/O split into `ios` CPU and I/O bursts, ios: I/O statements count
     pub fn io_bound(total_duration: u64, ios: u64) -> Self {
-        let total_cpu_duration = total_duration * 2 / 10;
-        let total_io_duration = total_duration - total_cpu_duration;
-        let cpu_duration = total_cpu_duration / ios;
-        let io_duration = total_io_duration / ios;
-        let mut statements = vec![];
-        for _ in 0..ios {
-            statements.push(Statement::cpu_bound(cpu_duration));
-            statements.push(Statement::io_bound(io_duration));
-        }
-        Self {
-            statements,
-            total_duration,
-            total_cpu_duration,
-            total_io_duration,
-            is_io_bound: true,
-        }
+        JobBuilder::new().ratio(total_duration, 20, ios).build()
+    }
+    /// Derives the totals from the statements.
+    pub fn from_statements(statements: Vec<Statement>) -> Self {
+        statements
+            .into_iter()
+            .fold(JobBuilder::new(), JobBuilder::statement)
+            .build()
+    }
+    /// A job which waits for its user between CPU bursts.
+    pub fn is_interactive(&self) -> bool {
+        self.total_think_duration > 0
+    }
+    /// A job which spends more time in I/O than on CPU is I/O bound.
+    pub fn is_io_bound(&self) -> bool {
+        self.total_io_duration > self.total_cpu_duration
     }
     pub fn type_hint(&self) -> &str {
-        if self.is_io_bound {
+        if self.is_interactive() {
+            "Interactive"
+        } else if self.is_io_bound() {
             "I/O Bound"
         } else {
             "CPU Bound"
@@ -50,3 +49,172 @@ impl Job {
         self.total_duration.checked_div(self.total_cpu_duration).unwrap_or(1)
     }
 }
+
+/// Composes a job from CPU and I/O bursts.
+///
+/// Zero length bursts are skipped and consecutive bursts of the same kind are merged,
+/// as an I/O burst has to be followed by a CPU burst to be awaited.
+pub struct JobBuilder {
+    statements: Vec<Statement>,
+    rng: fastrand::Rng,
+}
+
+impl JobBuilder {
+    pub fn new() -> Self {
+        Self {
+            statements: vec![],
+            rng: fastrand::Rng::new(),
+        }
+    }
+    /// Seeds the generator of the randomized bursts.
+    pub fn seed(self, seed: u64) -> Self {
+        self.rng.seed(seed);
+        self
+    }
+    pub fn statement(mut self, statement: Statement) -> Self {
+        if statement.duration() == 0 {
+            return self;
+        }
+        match (self.statements.last_mut(), statement) {
+            (Some(Statement::CpuBound(last)), Statement::CpuBound(duration))
+            | (Some(Statement::IoBound(last)), Statement::IoBound(duration))
+            | (Some(Statement::Think(last)), Statement::Think(duration)) => *last += duration,
+            _ => self.statements.push(statement),
+        }
+        self
+    }
+    pub fn cpu(self, duration: u64) -> Self {
+        self.statement(Statement::cpu_bound(duration))
+    }
+    pub fn io(self, duration: u64) -> Self {
+        self.statement(Statement::io_bound(duration))
+    }
+    pub fn think(self, duration: u64) -> Self {
+        self.statement(Statement::think(duration))
+    }
+    /// Appends `bursts` pairs of CPU and I/O bursts which take `total_duration` in total,
+    /// `cpu_percent` of it on CPU.
+    ///
+    /// The remainders of the divisions go to the first bursts, so the totals are exact.
+    /// The CPU gets at least 1 tick, as a job has to start with a CPU burst.
+    pub fn ratio(mut self, total_duration: u64, cpu_percent: u64, bursts: u64) -> Self {
+        assert!(cpu_percent <= 100, "cpu percent out of range: {}", cpu_percent);
+        let total_cpu_duration = (total_duration * cpu_percent / 100)
+            .max(1)
+            .min(total_duration);
+        let total_io_duration = total_duration - total_cpu_duration;
+        let bursts = bursts.max(1);
+        let split = |total: u64, i: u64| total / bursts + u64::from(i < total % bursts);
+        for i in 0..bursts {
+            self = self
+                .cpu(split(total_cpu_duration, i))
+                .io(split(total_io_duration, i));
+        }
+        self
+    }
+    /// Appends `bursts` pairs of CPU and I/O bursts with random lengths in the ranges.
+    pub fn random_bursts(mut self, bursts: u64, cpu: Range<u64>, io: Range<u64>) -> Self {
+        for _ in 0..bursts {
+            let cpu_duration = self.rng.u64(cpu.clone());
+            let io_duration = self.rng.u64(io.clone());
+            self = self.cpu(cpu_duration).io(io_duration);
+        }
+        self
+    }
+    /// Appends `cycles` interactions, each a CPU burst answering the user and the user's think time,
+    /// with random lengths in the ranges.
+    pub fn interactive(mut self, cycles: u64, cpu: Range<u64>, think: Range<u64>) -> Self {
+        for _ in 0..cycles {
+            let cpu_duration = self.rng.u64(cpu.clone());
+            let think_duration = self.rng.u64(think.clone());
+            self = self.cpu(cpu_duration).think(think_duration);
+        }
+        self
+    }
+    /// Repeats the bursts composed so far, `times` in total.
+    pub fn repeat(mut self, times: u64) -> Self {
+        let statements = self.statements.clone();
+        for _ in 1..times {
+            self = statements.iter().copied().fold(self, Self::statement);
+        }
+        self
+    }
+    pub fn build(self) -> Job {
+        assert!(
+            self.statements.first().map_or(true, Statement::is_cpu_bound),
+            "a job has to start with a CPU burst"
+        );
+        let total_cpu_duration = self
+            .statements
+            .iter()
+            .filter(|s| s.is_cpu_bound())
+            .map(|s| s.duration())
+            .sum::<u64>();
+        let total_io_duration = self
+            .statements
+            .iter()
+            .filter(|s| s.is_io_bound())
+            .map(|s| s.duration())
+            .sum::<u64>();
+        let total_think_duration = self
+            .statements
+            .iter()
+            .filter(|s| s.is_think())
+            .map(|s| s.duration())
+            .sum::<u64>();
+        Job {
+            statements: self.statements,
+            total_duration: total_cpu_duration + total_io_duration + total_think_duration,
+            total_cpu_duration,
+            total_io_duration,
+            total_think_duration,
+        }
+    }
+}
+
+impl Default for JobBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_bound_keeps_exact_totals() {
+        let job = Job::io_bound(1003, 4);
+        assert_eq!(job.total_duration, 1003);
+        assert_eq!(job.total_cpu_duration, 200);
+        assert_eq!(job.total_io_duration, 803);
+        assert_eq!(job.statements.len(), 8);
+        assert!(job.is_io_bound());
+        assert_eq!(
+            job.statements.iter().map(|s| s.duration()).sum::<u64>(),
+            job.total_duration
+        );
+    }
+
+    #[test]
+    fn short_io_bound_starts_with_cpu() {
+        let job = Job::io_bound(4, 2);
+        assert_eq!((job.total_cpu_duration, job.total_io_duration), (1, 3));
+        assert!(job.statements[0].is_cpu_bound());
+        assert_eq!(Job::io_bound(0, 2).total_duration, 0);
+    }
+
+    #[test]
+    fn merge_and_repeat_bursts() {
+        let job = JobBuilder::new().cpu(3).cpu(2).io(0).io(4).repeat(2).build();
+        let durations = job.statements.iter().map(|s| s.duration()).collect::<Vec<_>>();
+        assert_eq!(durations, vec![5, 4, 5, 4]);
+        assert!(!job.is_io_bound());
+        let job = JobBuilder::new()
+            .seed(7)
+            .random_bursts(10, 1..5, 10..20)
+            .build();
+        assert!(job.is_io_bound());
+        assert_eq!(job.statements.len(), 20);
+    }
+}
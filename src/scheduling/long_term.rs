@@ -0,0 +1,115 @@
+//! Long-Term Scheduling
+//!
+//! Arriving jobs wait in a job pool and are admitted, becoming ready, only while the degree of
+//! multiprogramming, the admitted processes which have not completed, is under a cap.
+//! The time a job spends in the pool is its admission delay, which is not counted as waiting.
+use std::cmp::Reverse;
+
+use crate::scheduling::{PId, Process};
+
+/// Which job in the pool is admitted next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdmissionPolicy {
+    /// the one which arrived first
+    FirstComeFirstServe,
+    /// the first one of the kind, I/O-bound or CPU-bound, fewer admitted processes are of,
+    /// so neither the CPU nor the devices sit idle
+    BalancedMix,
+    /// the one with the least CPU time
+    ShortestJobFirst,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct LongTermScheduler {
+    /// most admitted processes which have not completed
+    pub degree_of_multiprogramming: usize,
+    pub policy: AdmissionPolicy,
+}
+
+impl LongTermScheduler {
+    pub fn new(degree_of_multiprogramming: usize, policy: AdmissionPolicy) -> Self {
+        Self {
+            degree_of_multiprogramming: degree_of_multiprogramming.max(1),
+            policy,
+        }
+    }
+    /// The job to admit from `pool`, in arrival order, next to the `admitted` processes.
+    pub fn select<'a>(
+        &self,
+        pool: impl Iterator<Item = &'a Process>,
+        admitted: impl Iterator<Item = &'a Process>,
+    ) -> Option<PId> {
+        let mut pool = pool.enumerate();
+        match self.policy {
+            AdmissionPolicy::FirstComeFirstServe => pool.next().map(|(_, p)| p.id),
+            AdmissionPolicy::ShortestJobFirst => pool
+                .min_by_key(|(position, p)| (p.job.total_cpu_duration, *position))
+                .map(|(_, p)| p.id),
+            AdmissionPolicy::BalancedMix => {
+                let (io_bound, cpu_bound) = admitted.fold((0, 0), |(io, cpu), p| {
+                    if p.job.is_io_bound() {
+                        (io + 1, cpu)
+                    } else {
+                        (io, cpu + 1)
+                    }
+                });
+                let wants_io_bound = io_bound < cpu_bound;
+                // the first of the wanted kind, or the first of all if there is none
+                pool.max_by_key(|(position, p)| {
+                    (p.job.is_io_bound() == wants_io_bound, Reverse(*position))
+                })
+                .map(|(_, p)| p.id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::{FirstComeFirstServeScheduler, Os};
+
+    fn run(policy: AdmissionPolicy) -> Os {
+        let jobs = vec![
+            JobBuilder::new().cpu(20),
+            JobBuilder::new().cpu(30),
+            JobBuilder::new().cpu(15),
+            JobBuilder::new().cpu(3).io(40).cpu(3),
+            JobBuilder::new().cpu(5),
+        ];
+        let long_term = LongTermScheduler::new(2, policy);
+        let scenario = Scenario::new("admission", Box::new(FirstComeFirstServeScheduler::new()))
+            .configure(move |os| os.with_long_term(long_term));
+        jobs.into_iter()
+            .enumerate()
+            .fold(scenario, |scenario, (pid, job)| {
+                scenario.process_with_job(pid, 0, job.build())
+            })
+            .run()
+            .into_os()
+    }
+
+    fn admission_delays(os: &Os) -> Vec<u64> {
+        (0..5)
+            .map(|pid| os.get_process(pid).unwrap().admission_delay())
+            .collect()
+    }
+
+    #[test]
+    fn admits_under_the_degree_of_multiprogramming() {
+        let os = run(AdmissionPolicy::FirstComeFirstServe);
+        assert_eq!(admission_delays(&os), vec![0, 0, 20, 50, 65]);
+        // the 50 ticks in the pool are not waiting
+        let p3 = os.get_process(3).unwrap();
+        assert_eq!((p3.turn_around_time(), p3.waiting_time()), (111, 55));
+        let os = run(AdmissionPolicy::ShortestJobFirst);
+        assert_eq!(admission_delays(&os), vec![0, 0, 55, 50, 20]);
+        // P1 is CPU-bound so the I/O-bound P3 goes first, then the CPU-bound P2 joins P3
+        let os = run(AdmissionPolicy::BalancedMix);
+        assert_eq!(admission_delays(&os), vec![0, 0, 50, 20, 68]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Medium-Term Scheduling
+//!
+//! When too many processes, or too much memory, would be in memory at once, whole processes are
+//! swapped out to the backing store: a blocked one becomes suspended-blocked, and an arriving one
+//! waits as suspended-ready. Together with new and terminated, this is the seven-state model.
+//!
+//! A suspended-blocked process whose I/O completes becomes suspended-ready, and suspended-ready
+//! processes are swapped back in, the longest suspended first, as soon as they fit.
+//! When they do not fit and nothing in memory is ready to run, blocked processes are swapped
+//! out to make room, so the CPU does not idle while there is work on the backing store.
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{PId, ProcessState};
+
+/// Which blocked process is swapped out first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwapPolicy {
+    /// the one whose I/O completes last, as it would hold memory for the longest without running
+    LatestWakeUp,
+    /// the one taking the most memory, to make room with the fewest swaps
+    Largest,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediumTermScheduler {
+    /// most processes in memory at once
+    pub multiprogramming_limit: Option<usize>,
+    /// most units of memory taken at once
+    pub memory_limit: Option<u64>,
+    pub policy: SwapPolicy,
+}
+
+impl MediumTermScheduler {
+    pub fn new(policy: SwapPolicy) -> Self {
+        Self {
+            multiprogramming_limit: None,
+            memory_limit: None,
+            policy,
+        }
+    }
+    pub fn multiprogramming_limit(mut self, limit: usize) -> Self {
+        self.multiprogramming_limit = Some(limit.max(1));
+        self
+    }
+    pub fn memory_limit(mut self, limit: u64) -> Self {
+        self.memory_limit = Some(limit);
+        self
+    }
+    /// Whether a process taking `memory` fits next to `processes` taking `used` units.
+    pub fn fits(&self, processes: usize, used: u64, memory: u64) -> bool {
+        self.multiprogramming_limit
+            .map_or(true, |limit| processes < limit)
+            && self
+                .memory_limit
+                .map_or(true, |limit| used + memory <= limit)
+    }
+}
+
+/// A period in which a process was swapped out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Suspension {
+    pub pid: PId,
+    /// `SuspendedReady` or `SuspendedBlocked`
+    pub state: ProcessState,
+    pub start: Ticks,
+    pub end: Ticks,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::FirstComeFirstServeScheduler;
+
+    #[test]
+    fn swaps_out_blocked_processes_to_run_suspended_ones() {
+        // P0 blocks for long, P1 arrives while it is blocked, P2 arrives while P1 runs
+        let medium_term =
+            MediumTermScheduler::new(SwapPolicy::LatestWakeUp).multiprogramming_limit(1);
+        let scheduler = Box::new(FirstComeFirstServeScheduler::new());
+        let os = Scenario::new("suspend", scheduler)
+            .process_with_job(0, 0, JobBuilder::new().cpu(5).io(50).cpu(5).build())
+            .process(1, 10, 10)
+            .process(2, 12, 10)
+            .configure(move |os| os.with_medium_term(medium_term))
+            .run()
+            .into_os();
+        let suspensions = os
+            .suspensions()
+            .iter()
+            .map(|s| (s.pid, s.state, s.start.0, s.end.0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            suspensions,
+            vec![
+                (2, ProcessState::SuspendedReady, 12, 20),
+                (0, ProcessState::SuspendedBlocked, 10, 55),
+            ]
+        );
+        // P2 is swapped in once P1 completes, P0 once its I/O completes
+        assert_eq!(os.swaps(), (2, 1));
+        assert_eq!(os.get_process(0).unwrap().completion_time(), Ticks(60));
+        assert_eq!(os.stats().suspended_time, 8 + 45);
+        let narrative = os.narrative();
+        assert_eq!(
+            narrative.lines().collect::<Vec<_>>(),
+            vec![
+                "[0, 5) P0",
+                "[5, 10) idle",
+                "[10, 55) P0 SuspendedBlocked",
+                "[10, 20) P1",
+                "[12, 20) P2 SuspendedReady",
+                "[20, 30) P2",
+                "[30, 55) idle",
+                "[55, 60) P0",
+            ]
+        );
+    }
+}
@@ -1,12 +1,30 @@
 pub use job::Job;
-pub use os::Os;
-pub use process::{PId, Process};
+pub use os::{Os, TimeSlice};
+pub use process::{PId, Process, ProcessState};
 pub use scheduler::*;
 
+pub mod analysis;
+pub mod cache;
+pub mod explanation;
+pub mod interactive;
+pub mod interrupt;
 pub mod job;
+pub mod long_term;
+pub mod medium_term;
 pub mod os;
+pub mod power;
 pub mod process;
+pub mod queueing;
+pub mod replication;
+pub mod report;
+pub mod result;
+pub mod runtime;
+pub mod scenario;
 pub mod scheduler;
 pub mod statement;
+pub mod thread;
+pub mod timer;
+pub mod trace;
+pub mod watchdog;
 
 const TICK: u64 = 1;
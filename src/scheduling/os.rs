@@ -1,22 +1,79 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::ops::Add;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
 
-use indexmap::IndexMap;
-use pendulum::{HashedWheel, HashedWheelBuilder, Pendulum};
+use indexmap::{IndexMap, IndexSet};
 
-use crate::scheduling::{PId, Process, Scheduler, TICK};
+use crate::scheduling::cache::CacheModel;
+use crate::scheduling::explanation::Explanation;
+use crate::scheduling::interrupt::{Interrupt, InterruptModel};
+use crate::scheduling::long_term::LongTermScheduler;
+use crate::scheduling::medium_term::{MediumTermScheduler, Suspension, SwapPolicy};
+use crate::scheduling::power::{FrequencyLevel, PowerModel, PowerUsage};
+use crate::scheduling::report::Report;
+use crate::scheduling::timer::{HeapTimer, Ticks, Timer};
+use crate::scheduling::watchdog::{Stall, Watchdog};
+use crate::scheduling::{PId, Process, ProcessState, Scheduler, TICK};
 
 pub struct Os {
-    pub(crate) clock: u64,
+    pub(crate) clock: Ticks,
     processes: IndexMap<PId, Process>,
-    pub(crate) waiting: HashedWheel<PId>,
+    /// arriving processes and processes blocked on I/O
+    waiting: Box<dyn Timer<PId> + Send>,
     running_process_pid: Option<PId>,
     // thread-safe actually not needed
     scheduler: Arc<Mutex<Box<dyn Scheduler + Send>>>,
     completed_process_count: usize,
     context_switch_times: usize,
+    preemption_times: usize,
     jobs_desc: String,
+    timeline: Vec<TimeSlice>,
+    watchdog: Watchdog,
+    /// pid -> clock when it was flagged as starving
+    starved: IndexMap<PId, Ticks>,
+    stalled_ticks: u64,
+    stall: Option<Stall>,
+    cache_model: Option<CacheModel>,
+    /// processes which have run, the most recent last
+    recency: IndexSet<PId>,
+    interrupt_model: Option<InterruptModel>,
+    interrupt_count: usize,
+    /// handler ticks raised but not run yet
+    pending_handler: u64,
+    system_time: u64,
+    /// `None` unless explanations are on
+    explanations: Option<Vec<Explanation>>,
+    /// given by the scheduler before the switch it explains
+    pending_explanation: Option<Explanation>,
+    power_model: Option<PowerModel>,
+    frequency_level: usize,
+    /// progress earned at a lower frequency, in percent of a tick
+    speed_credit: u64,
+    /// ticks left to wake up from sleep
+    waking: u64,
+    idle_streak: u64,
+    power_usage: PowerUsage,
+    medium_term: Option<MediumTermScheduler>,
+    /// suspended-ready processes, the longest suspended first
+    suspended: IndexSet<PId>,
+    /// pid -> clock when its I/O completes
+    wake_at: HashMap<PId, Ticks>,
+    suspensions: Vec<Suspension>,
+    swap_ins: usize,
+    swap_outs: usize,
+    long_term: Option<LongTermScheduler>,
+    /// arrived jobs not admitted yet, in arrival order
+    job_pool: IndexSet<PId>,
+}
+
+/// A continuous period in which the CPU ran the same process, or was idle if `pid` is `None`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeSlice {
+    pub pid: Option<PId>,
+    pub start: Ticks,
+    pub end: Ticks,
 }
 
 impl Os {
@@ -25,49 +82,423 @@ impl Os {
         scheduler: Box<dyn Scheduler + Send>,
         jobs_desc: impl Into<String>,
     ) -> Self {
-        let mut waiting = HashedWheelBuilder::default()
-            .with_tick_duration(Duration::from_millis(TICK))
-            .with_max_timeout(Duration::from_secs(1000))
-            .build();
-        for p in processes.values() {
-            waiting
-                .insert_timeout(Duration::from_millis(p.arrival_time()), p.id)
-                .expect("timer error");
-        }
-        Self {
-            clock: 0,
+        let mut os = Self {
+            clock: Ticks::ZERO,
             processes,
-            waiting,
+            waiting: Box::new(HeapTimer::new()),
             running_process_pid: None,
             scheduler: Arc::new(Mutex::new(scheduler)),
             completed_process_count: 0,
             context_switch_times: 0,
+            preemption_times: 0,
             jobs_desc: jobs_desc.into(),
+            timeline: Vec::new(),
+            watchdog: Watchdog::default(),
+            starved: IndexMap::new(),
+            stalled_ticks: 0,
+            stall: None,
+            cache_model: None,
+            recency: IndexSet::new(),
+            interrupt_model: None,
+            interrupt_count: 0,
+            pending_handler: 0,
+            system_time: 0,
+            explanations: None,
+            pending_explanation: None,
+            power_model: None,
+            frequency_level: 0,
+            speed_credit: 0,
+            waking: 0,
+            idle_streak: 0,
+            power_usage: PowerUsage::default(),
+            medium_term: None,
+            suspended: IndexSet::new(),
+            wake_at: HashMap::new(),
+            suspensions: vec![],
+            swap_ins: 0,
+            swap_outs: 0,
+            long_term: None,
+            job_pool: IndexSet::new(),
+        };
+        os.schedule_arrivals();
+        os
+    }
+    /// Replaces the timer, which must not be used by another `Os`.
+    ///
+    /// Panics after the first tick, when the replaced timer may hold blocked processes.
+    pub fn with_timer(mut self, timer: impl Timer<PId> + Send + 'static) -> Self {
+        assert!(
+            self.clock == Ticks::ZERO && self.timeline.is_empty(),
+            "the timer must be replaced before the first tick"
+        );
+        self.waiting = Box::new(timer);
+        self.schedule_arrivals();
+        self
+    }
+    fn schedule_arrivals(&mut self) {
+        for p in self.processes.values() {
+            self.waiting.insert(p.arrival_time(), p.id);
         }
     }
+    /// Charges warm-up ticks to processes dispatched with a cold cache.
+    pub fn with_cache_model(mut self, cache_model: CacheModel) -> Self {
+        self.cache_model = Some(cache_model);
+        self
+    }
+    /// Raises timer and I/O completion interrupts whose handlers take CPU ticks.
+    pub fn with_interrupts(mut self, interrupt_model: InterruptModel) -> Self {
+        self.interrupt_model = Some(interrupt_model);
+        self
+    }
+    /// Accounts energy by power state, starting at the full speed.
+    pub fn with_power_model(mut self, power_model: PowerModel) -> Self {
+        self.frequency_level = power_model.full_speed_level();
+        self.power_model = Some(power_model);
+        self
+    }
+    /// Swaps whole processes out when the memory or multiprogramming limit is exceeded.
+    pub fn with_medium_term(mut self, medium_term: MediumTermScheduler) -> Self {
+        self.medium_term = Some(medium_term);
+        self
+    }
+    /// Keeps arriving jobs in a job pool until the degree of multiprogramming allows them in.
+    pub fn with_long_term(mut self, long_term: LongTermScheduler) -> Self {
+        self.long_term = Some(long_term);
+        self
+    }
+    /// Keeps why every process was dispatched, for the schedulers which can explain it.
+    pub fn with_explanations(mut self) -> Self {
+        self.explanations = Some(vec![]);
+        self
+    }
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = watchdog;
+        self
+    }
     pub fn run(&mut self) {
-        while !self.is_completed() {
+        while !self.is_completed() && self.stall.is_none() {
             self.tick();
         }
     }
+    /// Runs one tick of simulated time, the clock is advanced by the scheduler's burst.
     pub fn tick(&mut self) {
-        self.clock += TICK;
-        self.waiting.tick();
+        self.waiting.advance(self.clock);
+        if self
+            .interrupt_model
+            .map_or(false, |model| model.is_timer_due(self.clock))
+        {
+            self.raise(Interrupt::Timer);
+        }
         let scheduler = self.scheduler.clone();
         let mut scheduler = scheduler.lock().expect("lock failed");
         scheduler.on_tick(self);
+        drop(scheduler);
+        self.account_power();
+        self.watch();
+    }
+    fn raise(&mut self, interrupt: Interrupt) {
+        if let Some(model) = self.interrupt_model {
+            log::trace!("Clock[{}]: Interrupt::{:?}", self.clock, interrupt);
+            self.interrupt_count += 1;
+            self.pending_handler += model.handler(interrupt);
+        }
+    }
+    /// Spends the tick running a pending interrupt handler, in the context of the running process.
+    pub fn run_interrupt_handler(&mut self) -> bool {
+        if self.pending_handler == 0 {
+            return false;
+        }
+        self.pending_handler -= 1;
+        self.system_time += TICK;
+        if let Some(process) = self.running_process() {
+            process.charge_system_time(TICK);
+        }
+        true
+    }
+    fn is_asleep(&self) -> bool {
+        self.power_model
+            .as_ref()
+            .and_then(|model| model.sleep_after)
+            .map_or(false, |sleep_after| self.idle_streak > sleep_after)
+    }
+    /// Charges the tick which ends at the current clock to a power state.
+    fn account_power(&mut self) {
+        let model = match &self.power_model {
+            Some(model) => model,
+            None => return,
+        };
+        let is_busy = self
+            .timeline
+            .last()
+            .map_or(false, |slice| slice.pid.is_some() && slice.end == self.clock);
+        if is_busy {
+            self.idle_streak = 0;
+            self.power_usage.active_time += TICK;
+            self.power_usage.energy += model.levels()[self.frequency_level].active_power * TICK;
+            return;
+        }
+        self.idle_streak += TICK;
+        if self.is_asleep() {
+            self.power_usage.sleep_time += TICK;
+            self.power_usage.energy += model.sleep_power * TICK;
+        } else {
+            self.power_usage.idle_time += TICK;
+            self.power_usage.energy += model.idle_power * TICK;
+        }
+    }
+    /// Spends the tick of the running process waking up the CPU, or without progress because
+    /// of a lower frequency, which runs `speed` percent of a tick's work per tick.
+    pub fn stall_for_power(&mut self) -> bool {
+        let speed = match (&self.power_model, self.running_process_pid) {
+            (Some(model), Some(_)) => model.levels()[self.frequency_level].speed,
+            _ => return false,
+        };
+        if self.waking > 0 {
+            self.waking -= TICK;
+        } else {
+            self.speed_credit += speed;
+            if self.speed_credit >= 100 {
+                self.speed_credit -= 100;
+                return false;
+            }
+            self.power_usage.slowed_time += TICK;
+        }
+        if let Some(process) = self.running_process() {
+            process.charge_stalled_time(TICK);
+        }
+        true
+    }
+    /// Switches to a frequency level of the power model, the slowest is 0.
+    pub fn set_frequency(&mut self, level: usize) {
+        if let Some(model) = &self.power_model {
+            let level = level.min(model.full_speed_level());
+            if level != self.frequency_level {
+                log::trace!("Clock[{}]: Frequency Level[{}]", self.clock, level);
+            }
+            self.frequency_level = level;
+        }
+    }
+    pub fn frequency_level(&self) -> usize {
+        self.frequency_level
+    }
+    pub fn frequency(&self) -> Option<FrequencyLevel> {
+        self.power_model
+            .as_ref()
+            .map(|model| model.levels()[self.frequency_level])
+    }
+    pub fn power_model(&self) -> Option<&PowerModel> {
+        self.power_model.as_ref()
+    }
+    pub fn power_usage(&self) -> PowerUsage {
+        self.power_usage
+    }
+    pub fn long_term(&self) -> Option<LongTermScheduler> {
+        self.long_term
+    }
+    pub fn medium_term(&self) -> Option<MediumTermScheduler> {
+        self.medium_term
+    }
+    /// Periods in which processes were swapped out, in the order they ended.
+    pub fn suspensions(&self) -> &[Suspension] {
+        &self.suspensions
+    }
+    /// Processes swapped in and out. Every process leaving a suspended state into memory is
+    /// swapped in, so suspended arrivals are swapped in but never out.
+    pub fn swaps(&self) -> (usize, usize) {
+        (self.swap_ins, self.swap_outs)
+    }
+    fn watch(&mut self) {
+        let clock = self.clock;
+        if let Some(threshold) = self.watchdog.starvation_threshold {
+            for p in self.processes.values() {
+                if p.ready_wait(clock) > threshold && !self.starved.contains_key(&p.id) {
+                    log::warn!(
+                        "Clock[{}]: Process[{}] Starving, Ready for {}",
+                        clock,
+                        p.id,
+                        p.ready_wait(clock)
+                    );
+                    self.starved.insert(p.id, clock);
+                }
+            }
+        }
+        let is_bursting = self
+            .timeline
+            .last()
+            .map_or(false, |slice| slice.pid.is_some() && slice.end == clock);
+        // arriving and waiting processes are all in the timer
+        let is_timer_pending = !self.waiting.is_empty();
+        if is_bursting || is_timer_pending || self.is_completed() {
+            self.stalled_ticks = 0;
+        } else {
+            self.stalled_ticks += TICK;
+        }
+        if let Some(stall_limit) = self.watchdog.stall_limit {
+            if self.stalled_ticks >= stall_limit {
+                let stall = Stall {
+                    clock,
+                    stalled_ticks: self.stalled_ticks,
+                };
+                log::warn!("{}", stall);
+                self.stall = Some(stall);
+            }
+        }
     }
 
     pub fn running_process(&mut self) -> Option<&mut Process> {
         self.running_process_pid
             .and_then(move |pid| self.processes.get_mut(&pid))
     }
+    pub fn running_process_pid(&self) -> Option<PId> {
+        self.running_process_pid
+    }
     pub fn get_process(&self, pid: PId) -> Option<&Process> {
         self.processes.get(&pid)
     }
     pub fn get_mut_process(&mut self, pid: PId) -> Option<&mut Process> {
         self.processes.get_mut(&pid)
     }
+    /// Pops a process whose arrival or I/O has finished, it becomes ready.
+    ///
+    /// With a medium-term scheduler, suspended-ready processes which fit are swapped in first,
+    /// and processes which do not fit stay, or become, suspended-ready.
+    /// With a long-term scheduler, arriving jobs go to the job pool and are admitted from it.
+    pub fn expired_process(&mut self) -> Option<PId> {
+        loop {
+            if let Some(pid) = self.swap_in() {
+                return Some(pid);
+            }
+            if let Some(pid) = self.admit_job() {
+                if self.load(pid) {
+                    return Some(pid);
+                }
+                continue;
+            }
+            let pid = self.waiting.pop_expired()?;
+            let state = self.get_process(pid).map(|p| p.state());
+            if state == Some(ProcessState::New) && self.long_term.is_some() {
+                log::trace!("Clock[{}]: Process[{}] Job Pool", self.clock, pid);
+                self.job_pool.insert(pid);
+                continue;
+            }
+            if state.map_or(false, ProcessState::is_blocked) {
+                self.raise(Interrupt::IoCompletion);
+            }
+            // a blocked process is still in memory
+            if state == Some(ProcessState::Waiting) {
+                self.set_process_state(pid, ProcessState::Ready);
+                return Some(pid);
+            }
+            if self.load(pid) {
+                return Some(pid);
+            }
+        }
+    }
+    /// Makes `pid` ready if there is room in memory, otherwise suspends it.
+    fn load(&mut self, pid: PId) -> bool {
+        if self.suspended.is_empty() && self.make_room(pid) {
+            self.set_process_state(pid, ProcessState::Ready);
+            return true;
+        }
+        log::trace!("Clock[{}]: Process[{}] Suspended Ready", self.clock, pid);
+        self.set_process_state(pid, ProcessState::SuspendedReady);
+        self.suspended.insert(pid);
+        false
+    }
+    /// Takes a job out of the pool if the degree of multiprogramming allows.
+    fn admit_job(&mut self) -> Option<PId> {
+        let long_term = self.long_term?;
+        if self.job_pool.is_empty() {
+            return None;
+        }
+        let admitted = self
+            .processes
+            .values()
+            .filter(|p| p.state() != ProcessState::New && !p.is_completed());
+        if admitted.clone().count() >= long_term.degree_of_multiprogramming {
+            return None;
+        }
+        let pool = self.job_pool.iter().filter_map(|pid| self.processes.get(pid));
+        let pid = long_term.select(pool, admitted)?;
+        log::trace!("Clock[{}]: Process[{}] Admitted", self.clock, pid);
+        self.job_pool.shift_remove(&pid);
+        Some(pid)
+    }
+    /// Sets the state, keeping the periods spent swapped out.
+    fn set_process_state(&mut self, pid: PId, state: ProcessState) {
+        let clock = self.clock;
+        if let Some(process) = self.processes.get_mut(&pid) {
+            let (previous, since) = (process.state(), process.state_since());
+            process.set_state(state, clock);
+            if previous.is_suspended() && state.is_in_memory() {
+                self.swap_ins += 1;
+            }
+            if previous.is_suspended() && previous != state && clock > since {
+                self.suspensions.push(Suspension {
+                    pid,
+                    state: previous,
+                    start: since,
+                    end: clock,
+                });
+            }
+        }
+    }
+    fn swap_in(&mut self) -> Option<PId> {
+        let pid = *self.suspended.first()?;
+        if !self.make_room(pid) {
+            return None;
+        }
+        log::trace!("Clock[{}]: Process[{}] Swapped In", self.clock, pid);
+        self.suspended.shift_remove(&pid);
+        self.set_process_state(pid, ProcessState::Ready);
+        Some(pid)
+    }
+    /// Whether `pid` fits in memory, after swapping out blocked processes if nothing in memory
+    /// can run. A process always fits in an empty memory, or it would never run.
+    fn make_room(&mut self, pid: PId) -> bool {
+        let medium_term = match self.medium_term {
+            Some(medium_term) => medium_term,
+            None => return true,
+        };
+        let memory = self.get_process(pid).map_or(0, |p| p.memory());
+        loop {
+            let (count, used) = self
+                .processes
+                .values()
+                .filter(|p| p.state().is_in_memory())
+                .fold((0, 0), |(count, used), p| (count + 1, used + p.memory()));
+            if count == 0 || medium_term.fits(count, used, memory) {
+                return true;
+            }
+            let is_runnable = self.processes.values().any(|p| {
+                p.state() == ProcessState::Ready || p.state() == ProcessState::Running
+            });
+            if is_runnable {
+                return false;
+            }
+            let blocked = self
+                .processes
+                .values()
+                .filter(|p| p.state() == ProcessState::Waiting);
+            let victim = match medium_term.policy {
+                SwapPolicy::LatestWakeUp => blocked
+                    .max_by_key(|p| (self.wake_at.get(&p.id).copied(), Reverse(p.id)))
+                    .map(|p| p.id),
+                SwapPolicy::Largest => blocked
+                    .max_by_key(|p| (p.memory(), Reverse(p.id)))
+                    .map(|p| p.id),
+            };
+            match victim {
+                Some(victim) => {
+                    log::trace!("Clock[{}]: Process[{}] Swapped Out", self.clock, victim);
+                    self.swap_outs += 1;
+                    self.set_process_state(victim, ProcessState::SuspendedBlocked);
+                }
+                None => return false,
+            }
+        }
+    }
+    /// Blocks the process until `timeout` ticks after the current clock.
     pub fn await_process(&mut self, pid: PId, timeout: u64) {
         log::trace!(
             "Clock[{}]: Process[{}] was Awaited with Timeout[{}]",
@@ -75,9 +506,12 @@ impl Os {
             pid,
             timeout
         );
-        self.waiting
-            .insert_timeout(Duration::from_millis(timeout), pid)
-            .expect("timer error");
+        self.waiting.insert(self.clock + timeout, pid);
+        self.wake_at.insert(pid, self.clock + timeout);
+        let clock = self.clock;
+        if let Some(process) = self.get_mut_process(pid) {
+            process.set_state(ProcessState::Waiting, clock);
+        }
     }
     #[allow(unused)]
     pub fn complete_process(&mut self, pid: PId) {
@@ -89,6 +523,10 @@ impl Os {
             self.get_process(pid).unwrap().completion_time(),
             pid
         );
+        let clock = self.clock;
+        if let Some(process) = self.get_mut_process(pid) {
+            process.set_state(ProcessState::Terminated, clock);
+        }
         self.completed_process_count += 1;
         if self.is_completed() {
             self.clock = self
@@ -102,7 +540,57 @@ impl Os {
     pub fn is_completed(&self) -> bool {
         self.completed_process_count == self.processes.len()
     }
+    /// Asks the scheduler why it is about to switch, called by the scheduler before it picks.
+    pub fn explain(&mut self, scheduler: &(impl Scheduler + ?Sized)) {
+        if self.explanations.is_none() {
+            return;
+        }
+        let mut explanation = match scheduler.explain(self) {
+            Some(explanation) => explanation,
+            None => return,
+        };
+        // a preempted process is still running, off the ready queue the candidates come from
+        let incumbent = self
+            .running_process_pid
+            .filter(|&pid| {
+                self.get_process(pid)
+                    .map_or(false, |p| p.state() == ProcessState::Running)
+            })
+            .filter(|&pid| {
+                explanation
+                    .candidates
+                    .iter()
+                    .all(|&(candidate, _)| candidate != pid)
+            });
+        if let Some(pid) = incumbent {
+            if let Some(key) = scheduler.explain_key(self, pid) {
+                explanation.candidates.push((pid, key));
+            }
+        }
+        self.pending_explanation = Some(explanation);
+    }
+    fn record_explanation(&mut self, pid: Option<PId>) {
+        let mut explanation = match self.pending_explanation.take() {
+            Some(explanation) => explanation,
+            None => return,
+        };
+        // going idle is left to the timeline
+        if pid.is_none() {
+            return;
+        }
+        explanation.clock = self.clock;
+        explanation.previous = self.running_process_pid;
+        explanation.is_preemption = self
+            .running_process_pid
+            .and_then(|pid| self.get_process(pid))
+            .map_or(false, |p| p.state() == ProcessState::Running);
+        explanation.chosen = pid;
+        if let Some(explanations) = self.explanations.as_mut() {
+            explanations.push(explanation);
+        }
+    }
     pub fn switch_process(&mut self, pid: Option<PId>) {
+        self.record_explanation(pid);
         if self.running_process_pid == pid {
             return;
         }
@@ -116,21 +604,169 @@ impl Os {
             log::trace!("Clock[{}]: Idle", self.clock);
         }
         self.context_switch_times += 1;
+        let clock = self.clock;
+        if pid.is_some() && self.running_process_pid.is_none() && self.is_asleep() {
+            let wake_up_latency = self.power_model.as_ref().map_or(0, |m| m.wake_up_latency);
+            log::trace!("Clock[{}]: Wake Up for {}", clock, wake_up_latency);
+            self.waking = wake_up_latency;
+            self.power_usage.wake_ups += 1;
+        }
+        self.speed_credit = 0;
+        if let Some(process) = self.running_process() {
+            if process.state() == ProcessState::Running {
+                process.set_state(ProcessState::Ready, clock);
+                self.preemption_times += 1;
+            }
+        }
         self.running_process_pid = pid;
+        if let Some(pid) = pid {
+            let processes_run_since = self
+                .recency
+                .get_full(&pid)
+                .map_or(self.recency.len(), |(index, _)| self.recency.len() - index - 1);
+            self.recency.shift_remove(&pid);
+            self.recency.insert(pid);
+            let cache_model = self.cache_model;
+            if let Some(process) = self.running_process() {
+                let warm_up = cache_model.map_or(0, |cache_model| {
+                    cache_model.penalty(processes_run_since, process.last_ran_at(), clock)
+                });
+                if warm_up > 0 {
+                    log::trace!("Clock[{}]: Process[{}] Warms Up for {}", clock, pid, warm_up);
+                }
+                process.start_warm_up(processes_run_since, warm_up);
+                process.set_state(ProcessState::Running, clock);
+            }
+        }
     }
     pub fn is_process_running(&self, pid: PId) -> bool {
         self.running_process_pid
             .map_or(false, |running_pid| running_pid == pid)
     }
+    /// Records that `pid` held the CPU during the tick which ends at the current clock.
+    pub fn record_burst(&mut self, pid: Option<PId>) {
+        let clock = self.clock;
+        match self.timeline.last_mut() {
+            Some(slice) if slice.pid == pid && slice.end + TICK == clock => {
+                slice.end = clock
+            }
+            _ => self.timeline.push(TimeSlice {
+                pid,
+                start: Ticks(clock.0 - TICK),
+                end: clock,
+            }),
+        }
+    }
+    pub fn context_switch_times(&self) -> usize {
+        self.context_switch_times
+    }
+    pub fn interrupt_count(&self) -> usize {
+        self.interrupt_count
+    }
+    /// Ticks spent in interrupt handlers, idle ones included.
+    pub fn system_time(&self) -> u64 {
+        self.system_time
+    }
+    pub fn preemption_times(&self) -> usize {
+        self.preemption_times
+    }
+    /// Gantt chart of the run, idle periods included.
+    pub fn timeline(&self) -> &[TimeSlice] {
+        self.timeline.as_slice()
+    }
+    /// Explained dispatches, empty unless [`Os::with_explanations`] is used.
+    pub fn explanations(&self) -> &[Explanation] {
+        self.explanations.as_deref().unwrap_or(&[])
+    }
+    /// For schedulers which delegate to others, to amend the explanations of those.
+    pub(crate) fn explanations_mut(&mut self) -> &mut [Explanation] {
+        self.explanations.as_deref_mut().unwrap_or(&mut [])
+    }
+    /// The timeline with the explanation of every dispatch before the slice it starts,
+    /// and the periods processes spent swapped out from where they start.
+    pub fn narrative(&self) -> String {
+        let mut lines = vec![];
+        let mut explanations = self.explanations().iter().peekable();
+        let mut suspensions = self.suspensions.iter().collect::<Vec<_>>();
+        suspensions.sort_by_key(|s| (s.start, s.pid));
+        let mut suspensions = suspensions.into_iter().peekable();
+        let suspended =
+            |s: &Suspension| format!("[{}, {}) P{} {:?}", s.start, s.end, s.pid, s.state);
+        for slice in &self.timeline {
+            while let Some(suspension) = suspensions.next_if(|s| s.start <= slice.start) {
+                lines.push(suspended(suspension));
+            }
+            while let Some(explanation) = explanations.next_if(|e| e.clock <= slice.start) {
+                lines.push(explanation.to_string());
+            }
+            let pid = slice
+                .pid
+                .map_or_else(|| "idle".to_string(), |pid| format!("P{}", pid));
+            lines.push(format!("[{}, {}) {}", slice.start, slice.end, pid));
+        }
+        lines.extend(suspensions.map(suspended));
+        lines.extend(explanations.map(Explanation::to_string));
+        lines.join("\n")
+    }
+    /// Processes in the order they were dispatched, a process preempted and resumed later appears again.
+    pub fn dispatch_order(&self) -> Vec<PId> {
+        self.timeline.iter().filter_map(|slice| slice.pid).collect()
+    }
+    pub fn processes(&self) -> impl Iterator<Item = &Process> {
+        self.processes.values()
+    }
+    pub fn cache_model(&self) -> Option<CacheModel> {
+        self.cache_model
+    }
+    pub fn interrupt_model(&self) -> Option<InterruptModel> {
+        self.interrupt_model
+    }
+    pub fn jobs_desc(&self) -> &str {
+        &self.jobs_desc
+    }
+    pub fn scheduler_desc(&self) -> &'static str {
+        self.scheduler.lock().expect("lock failed").desc()
+    }
+    /// Processes flagged by the watchdog, with the clock when they were flagged.
+    pub fn starved_processes(&self) -> &IndexMap<PId, Ticks> {
+        &self.starved
+    }
+    /// Why the watchdog aborted the run, if it did.
+    pub fn stall(&self) -> Option<Stall> {
+        self.stall
+    }
+    /// Longest continuous wait in the ready state of the process, the ongoing one included.
+    pub fn longest_ready_wait(&self, pid: PId) -> u64 {
+        self.get_process(pid).map_or(0, |p| {
+            p.longest_ready_wait().max(p.ready_wait(self.clock))
+        })
+    }
 }
 
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct OsStats {
     pub average_waiting_time: u64,
+    /// ticks in the job pool, not counted as waiting
+    pub average_admission_delay: u64,
     pub average_turn_around_time: u64,
     pub average_weighted_turn_around_time: u64,
     pub cpu_usage: u64,
     pub context_switch_times: usize,
+    /// times a running process was switched out before it blocked or completed
+    pub preemptions: usize,
+    /// ticks spent refilling cold caches
+    pub warm_up_time: u64,
+    /// ticks spent running the processes
+    pub user_time: u64,
+    /// ticks spent in interrupt handlers
+    pub system_time: u64,
+    /// 0 without a power model
+    pub energy: u64,
+    /// swap-ins and swap-outs, a swap-in for every process leaving a suspended state into memory
+    pub swaps: usize,
+    /// ticks the processes spent swapped out
+    pub suspended_time: u64,
 }
 
 impl std::ops::Add<&Self> for OsStats {
@@ -138,10 +774,18 @@ impl std::ops::Add<&Self> for OsStats {
 
     fn add(mut self, rhs: &Self) -> Self::Output {
         self.average_waiting_time += rhs.average_waiting_time;
+        self.average_admission_delay += rhs.average_admission_delay;
         self.average_turn_around_time += rhs.average_turn_around_time;
         self.average_weighted_turn_around_time += rhs.average_weighted_turn_around_time;
         self.cpu_usage += rhs.cpu_usage;
         self.context_switch_times += rhs.context_switch_times;
+        self.preemptions += rhs.preemptions;
+        self.warm_up_time += rhs.warm_up_time;
+        self.user_time += rhs.user_time;
+        self.system_time += rhs.system_time;
+        self.energy += rhs.energy;
+        self.swaps += rhs.swaps;
+        self.suspended_time += rhs.suspended_time;
         self
     }
 }
@@ -154,113 +798,271 @@ impl OsStats {
         let mut stats = stats_list.iter().fold(Self::default(), Add::add);
         let stats_count = stats_list.len() as u64;
         stats.average_waiting_time /= stats_count;
+        stats.average_admission_delay /= stats_count;
         stats.average_turn_around_time /= stats_count;
         stats.average_weighted_turn_around_time /= stats_count;
         stats.context_switch_times /= stats_count as usize;
+        stats.preemptions /= stats_count as usize;
+        stats.warm_up_time /= stats_count;
+        stats.user_time /= stats_count;
+        stats.system_time /= stats_count;
+        stats.energy /= stats_count;
+        stats.swaps /= stats_count as usize;
+        stats.suspended_time /= stats_count;
         stats.cpu_usage /= stats_count;
         stats
     }
+    /// Cells of the stats, with the `+x%`/`-x%` diff against `average_stats` if given.
+    pub fn cells(
+        self,
+        jobs_desc: &str,
+        scheduler_desc: &str,
+        average_stats: Option<OsStats>,
+    ) -> Vec<String> {
+        let OsStats {
+            average_waiting_time,
+            average_admission_delay,
+            average_turn_around_time,
+            average_weighted_turn_around_time,
+            cpu_usage,
+            context_switch_times,
+            preemptions,
+            warm_up_time,
+            user_time,
+            system_time,
+            energy,
+            swaps,
+            suspended_time,
+        } = self;
+        let with_diff = |x: u64, ave: u64| -> String {
+            let (mark, diff) = if x >= ave {
+                ('+', x - ave)
+            } else {
+                ('-', ave - x)
+            };
+            format!("{}({}{}%)", x, mark, (diff * 100).checked_div(ave).unwrap_or(0))
+        };
+        if let Some(average_stats) = average_stats {
+            vec![
+                jobs_desc.to_string(),
+                scheduler_desc.to_string(),
+                with_diff(average_waiting_time, average_stats.average_waiting_time),
+                with_diff(
+                    average_admission_delay,
+                    average_stats.average_admission_delay,
+                ),
+                with_diff(average_turn_around_time, average_stats.average_turn_around_time),
+                with_diff(
+                    average_weighted_turn_around_time,
+                    average_stats.average_weighted_turn_around_time,
+                ),
+                format!("{}%", cpu_usage),
+                with_diff(
+                    context_switch_times as u64,
+                    average_stats.context_switch_times as u64,
+                ),
+                with_diff(preemptions as u64, average_stats.preemptions as u64),
+                with_diff(warm_up_time, average_stats.warm_up_time),
+                with_diff(user_time, average_stats.user_time),
+                with_diff(system_time, average_stats.system_time),
+                with_diff(energy, average_stats.energy),
+                with_diff(swaps as u64, average_stats.swaps as u64),
+                with_diff(suspended_time, average_stats.suspended_time),
+            ]
+        } else {
+            vec![
+                jobs_desc.to_string(),
+                scheduler_desc.to_string(),
+                average_waiting_time.to_string(),
+                average_admission_delay.to_string(),
+                average_turn_around_time.to_string(),
+                average_weighted_turn_around_time.to_string(),
+                format!("{}%", cpu_usage),
+                context_switch_times.to_string(),
+                preemptions.to_string(),
+                warm_up_time.to_string(),
+                user_time.to_string(),
+                system_time.to_string(),
+                energy.to_string(),
+                swaps.to_string(),
+                suspended_time.to_string(),
+            ]
+        }
+    }
 }
 
 impl Os {
+    /// The averages only count the completed processes, so they are partial when the watchdog
+    /// aborted the run, see [`Os::stall`].
     pub fn stats(&self) -> OsStats {
         let mut waiting_time_sum = 0;
         let mut turn_around_time_sum = 0;
         let mut weighted_turn_around_time_sum = 0;
         let mut burst_time_sum = 0;
+        let mut warm_up_time_sum = 0;
+        let mut suspended_time_sum = 0;
+        let mut admission_delay_sum = 0;
+        let mut process_count = 0;
         for p in self.processes.values() {
+            suspended_time_sum += p.suspended_time();
+            warm_up_time_sum += p.warm_up_time();
+            burst_time_sum += p.burst_time();
+            if !p.is_completed() {
+                continue;
+            }
+            process_count += 1;
+            admission_delay_sum += p.admission_delay();
             waiting_time_sum += p.waiting_time();
             turn_around_time_sum += p.turn_around_time();
             weighted_turn_around_time_sum += p.weighted_turn_around_time();
-            burst_time_sum += p.burst_time();
         }
-        let process_count = self.processes.len() as u64;
+        let process_count = process_count.max(1);
         let average_waiting_time = waiting_time_sum / process_count;
+        let average_admission_delay = admission_delay_sum / process_count;
         let average_turn_around_time = turn_around_time_sum / process_count;
         let average_weighted_turn_around_time = weighted_turn_around_time_sum / process_count;
-        let cpu_usage = burst_time_sum * 100 / self.clock;
+        let cpu_usage = burst_time_sum * 100 / self.clock.since(Ticks::ZERO);
         OsStats {
             average_waiting_time,
+            average_admission_delay,
             average_turn_around_time,
             average_weighted_turn_around_time,
             cpu_usage,
             context_switch_times: self.context_switch_times,
+            preemptions: self.preemption_times,
+            warm_up_time: warm_up_time_sum,
+            user_time: burst_time_sum,
+            system_time: self.system_time,
+            energy: self.power_usage.energy,
+            swaps: self.swap_ins + self.swap_outs,
+            suspended_time: suspended_time_sum,
         }
     }
-    pub fn detailed_process_stats_table(&self) -> prettytable::Table {
-        let mut table = prettytable::Table::new();
-        table.set_titles(Process::table_titles());
-        crate::utils::set_table_format(&mut table);
+    pub fn detailed_process_stats_report(&self) -> Report {
+        let mut report = Report::new(&Process::TABLE_TITLES);
         for p in self.processes.values() {
-            table.add_row(p.table_row());
+            report.add_row(p.table_cells());
         }
-        table
+        report
     }
+    pub fn detailed_process_stats_table(&self) -> prettytable::Table {
+        self.detailed_process_stats_report().to_table()
+    }
+    pub const TOTALLED_STATS_TITLES: [&'static str; 15] = [
+        "Job",
+        "Scheduler",
+        "Ave Waiting",
+        "Ave Admission",
+        "Ave Turn Around",
+        "Ave Wtd Turn Around",
+        "CPU Usage",
+        "Context Switches",
+        "Preemptions",
+        "Warm-up",
+        "User",
+        "System",
+        "Energy",
+        "Swaps",
+        "Suspended",
+    ];
     pub fn totalled_stats_titles() -> prettytable::Row {
-        row![
-            Fgb =>
-            "Job",
-            "Scheduler",
-            "Ave Waiting",
-            "Ave Turn Around",
-            "Ave Wtd Turn Around",
-            "CPU Usage",
-            "Context Switches"
-        ]
+        Report::new(&Self::TOTALLED_STATS_TITLES).title_row()
     }
-    pub fn stats_row(&self, stats: OsStats, average_stats: Option<OsStats>) -> prettytable::Row {
-        let OsStats {
-            average_waiting_time,
-            average_turn_around_time,
-            average_weighted_turn_around_time,
-            cpu_usage,
-            context_switch_times,
-        } = stats;
-        let with_diff = |x: u64, ave: u64| -> String {
-            let (mark, diff) = if x >= ave {
-                ('+', x - ave)
-            } else {
-                ('-', ave - x)
-            };
-            format!("{}({}{}%)", x, mark, diff * 100 / ave)
-        };
-        if let Some(average_stats) = average_stats {
-            row![
-                self.jobs_desc,
-                r->self.scheduler.lock().expect("lock failed").desc(),
-                with_diff(average_waiting_time,average_stats.average_waiting_time),
-                with_diff(average_turn_around_time,average_stats.average_turn_around_time),
-                with_diff(average_weighted_turn_around_time,average_stats.average_weighted_turn_around_time),
-                format!("{}%", cpu_usage),
-                with_diff(context_switch_times as u64, average_stats.context_switch_times as u64)
-            ]
-        } else {
-            row![
-                self.jobs_desc,
-                r->self.scheduler.lock().expect("lock failed").desc(),
-                average_waiting_time,
-                average_turn_around_time,
-                average_weighted_turn_around_time,
-                format!("{}%", cpu_usage),
-                context_switch_times
-            ]
+    /// See [`OsStats::cells`], the scheduler is marked when the stats are partial.
+    pub fn stats_cells(&self, stats: OsStats, average_stats: Option<OsStats>) -> Vec<String> {
+        let scheduler_desc = self.scheduler.lock().expect("lock failed").desc();
+        if self.stall.is_some() {
+            let scheduler_desc = format!("{} (Aborted)", scheduler_desc);
+            return stats.cells(&self.jobs_desc, &scheduler_desc, average_stats);
         }
+        stats.cells(&self.jobs_desc, scheduler_desc, average_stats)
+    }
+    pub fn stats_row(&self, stats: OsStats, average_stats: Option<OsStats>) -> prettytable::Row {
+        Report::new(&Self::TOTALLED_STATS_TITLES)
+            .align_right(1)
+            .table_row(&self.stats_cells(stats, average_stats))
+    }
+    pub fn stats_report(&self) -> Report {
+        let mut report = Report::new(&Self::TOTALLED_STATS_TITLES).align_right(1);
+        report.add_row(self.stats_cells(self.stats(), None));
+        report
     }
     pub fn stats_table(&self) -> prettytable::Table {
+        self.stats_report().to_table()
+    }
+    /// Stats of every os, with the diff against their average.
+    pub fn os_list_stats_report(os_list: &[Os]) -> Report {
+        let mut report = Report::new(&Self::TOTALLED_STATS_TITLES).align_right(1);
+        let stats_list = os_list.iter().map(|os| os.stats()).collect::<Vec<_>>();
+        let average_stats = OsStats::average_stats(stats_list.as_slice());
+        for (stats, os) in stats_list.into_iter().zip(os_list) {
+            report.add_row(os.stats_cells(stats, Some(average_stats)));
+        }
+        report
+    }
+    pub fn os_list_stats_table(os_list: &[Os]) -> prettytable::Table {
+        Self::os_list_stats_report(os_list).to_table()
+    }
+    pub fn starvation_table(&self) -> prettytable::Table {
         let mut table = prettytable::Table::new();
         crate::utils::set_table_format(&mut table);
-        table.set_titles(Self::totalled_stats_titles());
-        table.add_row(self.stats_row(self.stats(), None));
+        table.set_titles(row![
+            Fgb => "PId", "Job Type", "Longest Ready Wait", "Starved At", "Completed At"
+        ]);
+        for p in self.processes.values() {
+            let starved_at = self
+                .starved
+                .get(&p.id)
+                .map_or_else(|| "-".to_string(), |clock| clock.to_string());
+            // unfinished processes of an aborted run
+            let completed_at = if p.is_completed() {
+                p.completion_time().to_string()
+            } else {
+                "-".to_string()
+            };
+            table.add_row(row![
+                p.id,
+                p.job.type_hint(),
+                self.longest_ready_wait(p.id),
+                starved_at,
+                completed_at
+            ]);
+        }
         table
     }
-    pub fn os_list_stats_table(os_list: &[Os]) -> prettytable::Table {
+    pub fn starvation_row(&self) -> prettytable::Row {
+        let (longest_ready_wait, pid) = self
+            .processes
+            .keys()
+            .map(|&pid| (self.longest_ready_wait(pid), pid))
+            .max()
+            .unwrap_or((0, 0));
+        let status = self
+            .stall
+            .map_or_else(|| "Completed".to_string(), |stall| stall.to_string());
+        row![
+            self.jobs_desc,
+            r->self.scheduler.lock().expect("lock failed").desc(),
+            longest_ready_wait,
+            pid,
+            self.starved.len(),
+            status
+        ]
+    }
+    pub fn os_list_starvation_table(os_list: &[Os]) -> prettytable::Table {
         let mut table = prettytable::Table::new();
         crate::utils::set_table_format(&mut table);
-        table.set_titles(Self::totalled_stats_titles());
-        let stats_list = os_list.iter().map(|os| os.stats()).collect::<Vec<_>>();
-        let average_stats = OsStats::average_stats(stats_list.as_slice());
-        for (stats, os) in stats_list.into_iter().zip(os_list) {
-            table.add_row(os.stats_row(stats, Some(average_stats)));
+        table.set_titles(row![
+            Fgb =>
+            "Job",
+            "Scheduler",
+            "Longest Ready Wait",
+            "PId",
+            "Starved Processes",
+            "Status"
+        ]);
+        for os in os_list {
+            table.add_row(os.starvation_row());
         }
         table
     }
@@ -0,0 +1,148 @@
+//! CPU Power States
+//!
+//! A busy CPU draws the power of its frequency level, an idle one draws idle power,
+//! and after staying idle long enough it falls into a sleep state which draws even less
+//! but takes a few ticks to wake up from. Lower frequencies draw less power but also make
+//! less progress per tick, so running slowly is not always cheaper than racing to idle.
+//!
+//! Power is in milliwatts and a tick stands for a millisecond, so energy is in microjoules.
+
+/// A DVFS operating point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyLevel {
+    /// percent of the full speed, the progress made per tick
+    pub speed: u64,
+    pub active_power: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerModel {
+    /// frequency levels from the slowest to the full speed
+    levels: Vec<FrequencyLevel>,
+    pub idle_power: u64,
+    pub sleep_power: u64,
+    /// idle ticks after which the CPU sleeps, never if `None`
+    pub sleep_after: Option<u64>,
+    /// ticks spent waking up from sleep before the dispatched process makes progress
+    pub wake_up_latency: u64,
+}
+
+impl PowerModel {
+    /// A CPU with the full speed only and no sleep state.
+    pub fn new(active_power: u64, idle_power: u64) -> Self {
+        Self {
+            levels: vec![FrequencyLevel {
+                speed: 100,
+                active_power,
+            }],
+            idle_power,
+            sleep_power: idle_power,
+            sleep_after: None,
+            wake_up_latency: 0,
+        }
+    }
+    /// Adds a frequency level, `speed` in percent of the full speed, replacing one of the same speed.
+    pub fn level(mut self, speed: u64, active_power: u64) -> Self {
+        let speed = speed.clamp(1, 100);
+        self.levels.retain(|level| level.speed != speed);
+        self.levels.push(FrequencyLevel {
+            speed,
+            active_power,
+        });
+        self.levels.sort_by_key(|level| level.speed);
+        self
+    }
+    pub fn sleep(mut self, sleep_power: u64, sleep_after: u64, wake_up_latency: u64) -> Self {
+        self.sleep_power = sleep_power;
+        self.sleep_after = Some(sleep_after);
+        self.wake_up_latency = wake_up_latency;
+        self
+    }
+    /// A mobile-like CPU: 100 mW of static power plus dynamic power growing with the cube
+    /// of the frequency, 900 mW at full speed.
+    pub fn mobile() -> Self {
+        Self::new(1000, 100)
+            .level(75, 480)
+            .level(50, 212)
+            .level(25, 114)
+            .sleep(5, 5, 2)
+    }
+    pub fn levels(&self) -> &[FrequencyLevel] {
+        &self.levels
+    }
+    pub fn full_speed_level(&self) -> usize {
+        self.levels.len() - 1
+    }
+    /// The slowest level at least as fast as `speed`, the full speed if none is.
+    pub fn level_for(&self, speed: u64) -> usize {
+        self.levels
+            .iter()
+            .position(|level| level.speed >= speed)
+            .unwrap_or_else(|| self.full_speed_level())
+    }
+}
+
+/// Ticks in each power state and the energy drawn.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerUsage {
+    /// busy ticks, waking up included
+    pub active_time: u64,
+    pub idle_time: u64,
+    pub sleep_time: u64,
+    pub wake_ups: usize,
+    /// busy ticks which made no progress because of a lower frequency
+    pub slowed_time: u64,
+    pub energy: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::timer::Ticks;
+    use crate::scheduling::{FirstComeFirstServeScheduler, Os};
+
+    fn run(power_model: PowerModel, level: usize) -> Os {
+        let job = JobBuilder::new().cpu(10).io(20).cpu(10).build();
+        Scenario::new("power", Box::new(FirstComeFirstServeScheduler::new()))
+            .process_with_job(0, 0, job)
+            .configure(move |os| {
+                let mut os = os.with_power_model(power_model);
+                os.set_frequency(level);
+                os
+            })
+            .run()
+            .into_os()
+    }
+
+    #[test]
+    fn sleep_and_half_speed() {
+        let model = PowerModel::new(1000, 100).level(50, 200).sleep(10, 5, 2);
+        assert_eq!((model.level_for(30), model.level_for(80)), (0, 1));
+        let os = run(model.clone(), 1);
+        // 10 ticks of CPU, 5 idle, 15 asleep, 2 waking up and 10 more of CPU
+        let process = os.get_process(0).unwrap();
+        assert_eq!(process.completion_time(), Ticks(42));
+        // the I/O burst is the only wait, the wake-up is spent on the CPU
+        assert_eq!((process.stalled_time(), process.waiting_time()), (2, 20));
+        let usage = os.power_usage();
+        assert_eq!(
+            (usage.active_time, usage.idle_time, usage.sleep_time),
+            (22, 5, 15)
+        );
+        assert_eq!(usage.wake_ups, 1);
+        assert_eq!(usage.energy, 22 * 1000 + 5 * 100 + 15 * 10);
+        let os = run(model, 0);
+        // every other tick makes progress, so the bursts take twice as long
+        let process = os.get_process(0).unwrap();
+        assert_eq!(process.completion_time(), Ticks(62));
+        assert_eq!((process.stalled_time(), process.waiting_time()), (22, 20));
+        let usage = os.power_usage();
+        assert_eq!((usage.active_time, usage.slowed_time), (42, 20));
+        assert_eq!(usage.energy, 42 * 200 + 5 * 100 + 15 * 10);
+    }
+}
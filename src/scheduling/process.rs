@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
 use crate::scheduling::{Job, TICK};
+use crate::scheduling::report::Report;
 use crate::scheduling::statement::Statement;
+use crate::scheduling::timer::Ticks;
 
 pub type PId = usize;
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RunningStatement {
     index: usize,
     elapsed_time: u64,
@@ -26,82 +29,213 @@ impl RunningStatement {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProcessState {
+    New,
+    Ready,
+    Running,
+    Waiting,
+    Terminated,
+    /// ready but swapped out by the medium-term scheduler
+    SuspendedReady,
+    /// blocked and swapped out by the medium-term scheduler
+    SuspendedBlocked,
+}
+
+impl ProcessState {
+    pub fn is_suspended(self) -> bool {
+        matches!(self, ProcessState::SuspendedReady | ProcessState::SuspendedBlocked)
+    }
+    pub fn is_blocked(self) -> bool {
+        matches!(self, ProcessState::Waiting | ProcessState::SuspendedBlocked)
+    }
+    /// Ready, running or blocked in memory.
+    pub fn is_in_memory(self) -> bool {
+        matches!(
+            self,
+            ProcessState::Ready | ProcessState::Running | ProcessState::Waiting
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Process {
     pub id: PId,
     pub job: Arc<Job>,
-    arrival_time: u64,
-    completion_time: u64,
+    /// the process owning this thread, the process itself if single threaded
+    group: PId,
+    arrival_time: Ticks,
+    completion_time: Ticks,
     burst_time: u64,
     running_statement: Option<RunningStatement>,
+    state: ProcessState,
+    state_since: Ticks,
+    longest_ready_wait: u64,
+    /// clock when it last left the CPU
+    last_ran_at: Option<Ticks>,
+    /// distinct processes which ran between its last two dispatches
+    processes_run_since: usize,
+    warm_up_remaining: u64,
+    warm_up_time: u64,
+    /// interrupt handler ticks run while it was on the CPU
+    system_time: u64,
+    /// ticks on the CPU without progress, waking it up or slowed down by the power model
+    stalled_time: u64,
+    /// clock when the user's current request was made
+    interaction_started: Ticks,
+    response_times: Vec<u64>,
+    /// units of memory it takes when not swapped out
+    memory: u64,
+    suspended_time: u64,
+    /// ticks from the arrival until the long-term scheduler admitted it
+    admission_delay: u64,
 }
 
 impl Process {
-    pub fn new(id: usize, job: Arc<Job>, arrival_time: u64) -> Self {
+    pub fn new(id: usize, job: Arc<Job>, arrival_time: Ticks) -> Self {
         Self {
             id,
             job,
+            group: id,
             arrival_time,
             completion_time: arrival_time,
             burst_time: 0,
             running_statement: None,
+            state: ProcessState::New,
+            state_since: Ticks::ZERO,
+            longest_ready_wait: 0,
+            last_ran_at: None,
+            processes_run_since: 0,
+            warm_up_remaining: 0,
+            warm_up_time: 0,
+            system_time: 0,
+            stalled_time: 0,
+            interaction_started: arrival_time,
+            response_times: vec![],
+            memory: 1,
+            suspended_time: 0,
+            admission_delay: 0,
+        }
+    }
+    /// Marks the process as a kernel-level thread of the `group` process.
+    pub fn with_group(mut self, group: PId) -> Self {
+        self.group = group;
+        self
+    }
+    /// Sets the memory it takes, 1 unit by default.
+    pub fn with_memory(mut self, memory: u64) -> Self {
+        self.memory = memory;
+        self
+    }
+    pub fn set_state(&mut self, state: ProcessState, clock: Ticks) {
+        if self.state == state {
+            return;
+        }
+        if self.state == ProcessState::Running {
+            self.last_ran_at = Some(clock);
+            self.warm_up_remaining = 0;
+        }
+        if self.state.is_blocked() && !state.is_blocked() && self.is_back_from_think() {
+            self.interaction_started = clock;
         }
+        if self.state.is_suspended() {
+            self.suspended_time += clock.since(self.state_since);
+        }
+        if self.state == ProcessState::New {
+            self.admission_delay = clock.since(self.arrival_time);
+        }
+        self.longest_ready_wait = self.longest_ready_wait.max(self.ready_wait(clock));
+        self.state = state;
+        self.state_since = clock;
     }
-    pub fn complete(&mut self, completion_time: u64) {
+    pub fn complete(&mut self, completion_time: Ticks) {
         if self.is_completed() {
             return;
         }
-        self.completion_time = completion_time - TICK; // error-fixing
+        self.completion_time = completion_time;
         debug_assert!(self.burst_time() >= self.job.total_cpu_duration);
         debug_assert!(self.turn_around_time() >= self.job.total_duration);
         self.running_statement.take();
     }
+    /// Runs the current statement for one tick which ends at `clock`.
+    ///
     /// returns: new running statement
-    pub fn burst(&mut self, clock: u64) -> Option<Statement> {
+    pub fn burst(&mut self, clock: Ticks) -> Option<Statement> {
         if self.is_completed() {
             return None;
         }
-        let (running_statement, statement_if_new) = self
+        if self.statements().is_empty() {
+            self.complete(clock);
+            return None;
+        }
+        let running_statement = self
             .running_statement
             .take()
-            .map(|running_statement| {
-                let elapsed_time = running_statement.elapsed_time;
-                let running_statement_duration =
-                    self.job.statements[running_statement.index].duration();
-                if elapsed_time + TICK >= running_statement_duration {
-                    let next_statement_index = running_statement.index + 1;
-                    let next_statement = self
-                        .statements()
-                        .get(next_statement_index)
-                        .map(|_| RunningStatement::new(next_statement_index));
-                    (next_statement, next_statement)
-                } else {
-                    (Some(running_statement.elapsed(TICK)), None)
+            .unwrap_or_else(|| RunningStatement::new(0))
+            .elapsed(TICK);
+        self.burst_time += TICK;
+        if running_statement.elapsed_time >= self.statements()[running_statement.index].duration() {
+            let next_statement_index = running_statement.index + 1;
+            if let Some(next_statement) = self.statements().get(next_statement_index).copied() {
+                self.running_statement = Some(RunningStatement::new(next_statement_index));
+                if next_statement.is_think() {
+                    self.respond(clock);
                 }
-            })
-            .unwrap_or_else(|| {
-                if self.statements().is_empty() {
-                    (None, None)
-                } else {
-                    let next_statement = Some(RunningStatement::new(0));
-                    (next_statement, next_statement)
+                Some(next_statement)
+            } else {
+                self.complete(clock);
+                if self.job.is_interactive() {
+                    self.respond(clock);
                 }
-            });
-        if running_statement.is_none() {
-            self.complete(clock);
+                None
+            }
+        } else {
+            self.running_statement = Some(running_statement);
+            None
+        }
+    }
+    /// Called on dispatch, the next `warm_up` ticks on the CPU only refill the cache.
+    pub fn start_warm_up(&mut self, processes_run_since: usize, warm_up: u64) {
+        self.processes_run_since = processes_run_since;
+        self.warm_up_remaining = warm_up;
+    }
+    /// Spends the tick warming up the cache if still cold, without progress on the statement.
+    pub fn warm_up(&mut self) -> bool {
+        if self.warm_up_remaining == 0 {
+            return false;
         }
+        self.warm_up_remaining -= 1;
+        self.warm_up_time += TICK;
+        true
+    }
+    /// Whether the statement before the current one is the user thinking.
+    fn is_back_from_think(&self) -> bool {
+        self.running_statement
+            .and_then(|s| s.index.checked_sub(1))
+            .map_or(false, |index| self.statements()[index].is_think())
+    }
+    fn respond(&mut self, clock: Ticks) {
+        self.response_times.push(clock.since(self.interaction_started));
+    }
+    /// Time from each request of the user until the CPU burst answering it finished.
+    pub fn response_times(&self) -> &[u64] {
+        &self.response_times
+    }
+    /// Accounts one tick of CPU time for work which is not described by the job's statements.
+    pub(crate) fn account_burst(&mut self) {
         self.burst_time += TICK;
-        self.running_statement = running_statement;
-        statement_if_new.map(|s| self.statements()[s.index])
     }
     /// bump to next statement without incrementing burst time
-    pub fn bump_to_next(&mut self, clock: u64) -> Option<Statement> {
+    pub fn bump_to_next(&mut self, clock: Ticks) -> Option<Statement> {
         let running_statement = self.running_statement.take();
         let next_statement_index = running_statement.map(|s| s.index + 1).unwrap_or(0);
         let next_statement = self.statements().get(next_statement_index).copied();
         if next_statement.is_none() {
             if let Some(running_statement) = running_statement {
-                self.complete(clock + self.statements()[running_statement.index].duration());
+                let duration = self.statements()[running_statement.index].duration();
+                self.complete(clock + duration);
             } else {
                 self.complete(clock);
             }
@@ -123,14 +257,67 @@ impl Process {
         self.completion_time != self.arrival_time
     }
     pub fn is_io_bound(&self) -> bool {
-        self.job.is_io_bound
+        self.job.is_io_bound()
+    }
+    pub fn last_ran_at(&self) -> Option<Ticks> {
+        self.last_ran_at
+    }
+    pub fn processes_run_since(&self) -> usize {
+        self.processes_run_since
+    }
+    /// Ticks spent on the CPU warming up the cache, not included in the burst time.
+    pub fn warm_up_time(&self) -> u64 {
+        self.warm_up_time
+    }
+    pub fn charge_system_time(&mut self, ticks: u64) {
+        self.system_time += ticks;
+    }
+    /// Ticks of interrupt handlers run in its context, not included in the burst time.
+    pub fn system_time(&self) -> u64 {
+        self.system_time
+    }
+    pub fn charge_stalled_time(&mut self, ticks: u64) {
+        self.stalled_time += ticks;
+    }
+    /// Ticks on the CPU stalled by the power model, not included in the burst time.
+    pub fn stalled_time(&self) -> u64 {
+        self.stalled_time
+    }
+    pub fn memory(&self) -> u64 {
+        self.memory
+    }
+    /// Time swapped out, ready or blocked.
+    pub fn suspended_time(&self) -> u64 {
+        self.suspended_time
+    }
+    pub fn group(&self) -> PId {
+        self.group
+    }
+    pub fn state(&self) -> ProcessState {
+        self.state
+    }
+    /// Clock when it entered the current state.
+    pub fn state_since(&self) -> Ticks {
+        self.state_since
+    }
+    /// Time the process has been continuously waiting in the ready state until `clock`.
+    pub fn ready_wait(&self, clock: Ticks) -> u64 {
+        if self.state == ProcessState::Ready {
+            clock.since(self.state_since)
+        } else {
+            0
+        }
+    }
+    /// Longest continuous wait in the ready state, the ongoing one not included.
+    pub fn longest_ready_wait(&self) -> u64 {
+        self.longest_ready_wait
     }
     /// Time at which the process arrives in the ready queue.
-    pub fn arrival_time(&self) -> u64 {
+    pub fn arrival_time(&self) -> Ticks {
         self.arrival_time
     }
     /// Time at which process completes its execution.
-    pub fn completion_time(&self) -> u64 {
+    pub fn completion_time(&self) -> Ticks {
         self.completion_time
     }
     /// Time required by a process for CPU execution.
@@ -139,7 +326,7 @@ impl Process {
     }
     /// Time Difference between completion time and arrival time.
     pub fn turn_around_time(&self) -> u64 {
-        self.completion_time - self.arrival_time
+        self.completion_time.since(self.arrival_time)
     }
     /// turn around time divides burst time
     pub fn weighted_turn_around_time(&self) -> u64 {
@@ -147,44 +334,79 @@ impl Process {
             .checked_div(self.burst_time)
             .unwrap_or(0)
     }
-    /// Time Difference between turn around time and burst time.
+    /// Time Difference between turn around time and the time held on the CPU,
+    /// the admission delay not included.
     pub fn waiting_time(&self) -> u64 {
-        self.turn_around_time().saturating_sub(self.burst_time)
+        self.turn_around_time().saturating_sub(
+            self.burst_time
+                + self.warm_up_time
+                + self.system_time
+                + self.stalled_time
+                + self.admission_delay,
+        )
+    }
+    /// Time spent in the job pool before the long-term scheduler admitted it.
+    pub fn admission_delay(&self) -> u64 {
+        self.admission_delay
     }
     /// Time Difference between total cpu time and burst time.
+    ///
+    /// It is the burst time SJF, LJF and HRRN compare, so a process back from I/O
+    /// is compared by the CPU time it has left rather than by the whole job.
     pub fn remaining_time(&self) -> u64 {
         self.job.total_cpu_duration.saturating_sub(self.burst_time)
     }
+    /// Time left of the current statement, the first one if not started yet.
+    pub fn remaining_burst_time(&self) -> u64 {
+        if self.is_completed() {
+            return 0;
+        }
+        let (index, elapsed_time) = self
+            .running_statement
+            .map_or((0, 0), |s| (s.index, s.elapsed_time));
+        self.statements()
+            .get(index)
+            .map_or(0, |s| s.duration().saturating_sub(elapsed_time))
+    }
     pub fn statements(&self) -> &[Statement] {
         self.job.statements.as_ref()
     }
+    pub const TABLE_TITLES: [&'static str; 13] = [
+        "PId",
+        "Job Type",
+        "Total Duration",
+        "Total I/O Duration",
+        "Arrival",
+        "Completion",
+        "Burst",
+        "System",
+        "Suspended",
+        "Admission",
+        "Waiting",
+        "Turn Around",
+        "Weighted Turn Around",
+    ];
     pub fn table_titles() -> prettytable::Row {
-        row![
-            Fgb =>
-            "PId",
-            "Job Type",
-            "Total Duration",
-            "Total I/O Duration",
-            "Arrival",
-            "Completion",
-            "Burst",
-            "Waiting",
-            "Turn Around",
-            "Weighted Turn Around"
+        Report::new(&Self::TABLE_TITLES).title_row()
+    }
+    pub fn table_cells(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.job.type_hint().to_string(),
+            self.job.total_duration.to_string(),
+            self.job.total_io_duration.to_string(),
+            self.arrival_time.to_string(),
+            self.completion_time.to_string(),
+            self.burst_time.to_string(),
+            self.system_time.to_string(),
+            self.suspended_time.to_string(),
+            self.admission_delay.to_string(),
+            self.waiting_time().to_string(),
+            self.turn_around_time().to_string(),
+            self.weighted_turn_around_time().to_string(),
         ]
     }
     pub fn table_row(&self) -> prettytable::Row {
-        row![
-            self.id,
-            self.job.type_hint(),
-            self.job.total_duration,
-            self.job.total_io_duration,
-            self.arrival_time,
-            self.completion_time,
-            self.burst_time,
-            self.waiting_time(),
-            self.turn_around_time(),
-            self.weighted_turn_around_time()
-        ]
+        Report::new(&Self::TABLE_TITLES).table_row(&self.table_cells())
     }
 }
@@ -0,0 +1,250 @@
+//! Queueing Theory
+//!
+//! Validates the timing of [`Os`] against the analytical results of single server queues.
+//! Jobs arrive as a Poisson process and need a single CPU burst each,
+//! exponential for M/M/1 and of any distribution for M/G/1.
+//!
+//! - FCFS is the M/G/1 queue of the Pollaczek–Khinchine formula,
+//!   the mean waiting time is `λ·E[S²] / 2(1 - ρ)`.
+//! - Round robin with a quantum of one tick approximates processor sharing,
+//!   whose mean response time `E[S] / (1 - ρ)` depends on the mean service time only.
+//! - Little's law gives the mean number of jobs in the system, `L = λ·T`.
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::scheduling::job::JobBuilder;
+use crate::scheduling::report::Report;
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{
+    FirstComeFirstServeScheduler, Os, PId, Process, RoundRobinScheduler, Scheduler,
+};
+
+/// Distribution of the service times, all with the same mean.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ServiceTime {
+    /// M/M/1
+    Exponential,
+    /// M/D/1
+    Deterministic,
+    /// uniform between 0 and twice the mean
+    Uniform,
+}
+
+impl ServiceTime {
+    /// `E[S²]` of the distribution with mean `mean`.
+    pub fn second_moment(&self, mean: f64) -> f64 {
+        match self {
+            ServiceTime::Exponential => 2.0 * mean * mean,
+            ServiceTime::Deterministic => mean * mean,
+            ServiceTime::Uniform => 4.0 / 3.0 * mean * mean,
+        }
+    }
+    fn sample(&self, rng: &fastrand::Rng, mean: f64) -> f64 {
+        match self {
+            ServiceTime::Exponential => exponential(rng, mean),
+            ServiceTime::Deterministic => mean,
+            ServiceTime::Uniform => rng.f64() * 2.0 * mean,
+        }
+    }
+}
+
+fn exponential(rng: &fastrand::Rng, mean: f64) -> f64 {
+    -mean * (1.0 - rng.f64()).ln()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Discipline {
+    FirstComeFirstServe,
+    ProcessorSharing,
+}
+
+impl Discipline {
+    pub const ALL: [Discipline; 2] = [
+        Discipline::FirstComeFirstServe,
+        Discipline::ProcessorSharing,
+    ];
+    pub fn scheduler(&self) -> Box<dyn Scheduler + Send> {
+        match self {
+            Discipline::FirstComeFirstServe => Box::new(FirstComeFirstServeScheduler::new()),
+            Discipline::ProcessorSharing => Box::new(RoundRobinScheduler::new(1)),
+        }
+    }
+}
+
+/// Mean times in ticks, and the mean number of jobs in the system.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QueueMeans {
+    pub waiting: f64,
+    pub response: f64,
+    pub in_system: f64,
+}
+
+impl QueueMeans {
+    /// Measures a finished run, over the span from the first arrival to the last completion.
+    pub fn measure(os: &Os) -> Self {
+        let processes = os.processes().collect::<Vec<_>>();
+        let count = processes.len().max(1) as f64;
+        let start = processes
+            .iter()
+            .map(|p| p.arrival_time())
+            .min()
+            .unwrap_or_default();
+        let end = processes
+            .iter()
+            .map(|p| p.completion_time())
+            .max()
+            .unwrap_or_default();
+        let response = processes.iter().map(|p| p.turn_around_time()).sum::<u64>();
+        let waiting = processes.iter().map(|p| p.waiting_time()).sum::<u64>();
+        Self {
+            waiting: waiting as f64 / count,
+            response: response as f64 / count,
+            in_system: response as f64 / end.since(start).max(1) as f64,
+        }
+    }
+}
+
+/// A single server queue fed by Poisson arrivals, with rates in jobs per tick.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QueueModel {
+    pub arrival_rate: f64,
+    pub mean_service: f64,
+    pub service: ServiceTime,
+    pub jobs: usize,
+}
+
+impl QueueModel {
+    pub fn mm1(arrival_rate: f64, mean_service: f64) -> Self {
+        Self::mg1(arrival_rate, mean_service, ServiceTime::Exponential)
+    }
+    pub fn mg1(arrival_rate: f64, mean_service: f64, service: ServiceTime) -> Self {
+        Self {
+            arrival_rate,
+            mean_service,
+            service,
+            jobs: 10000,
+        }
+    }
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+    pub fn desc(&self) -> &'static str {
+        match self.service {
+            ServiceTime::Exponential => "M/M/1",
+            ServiceTime::Deterministic => "M/D/1",
+            ServiceTime::Uniform => "M/G/1 Uniform",
+        }
+    }
+    /// `ρ = λ·E[S]`, the queue is stable below 1.
+    pub fn utilization(&self) -> f64 {
+        self.arrival_rate * self.mean_service
+    }
+    /// Service times are rounded to whole ticks, at least one.
+    pub fn workload(&self, seed: u64) -> IndexMap<PId, Process> {
+        let rng = fastrand::Rng::new();
+        rng.seed(seed);
+        let mut clock = 0.0;
+        (0..self.jobs)
+            .map(|pid| {
+                clock += exponential(&rng, 1.0 / self.arrival_rate);
+                let service = self
+                    .service
+                    .sample(&rng, self.mean_service)
+                    .round()
+                    .max(1.0);
+                let job = JobBuilder::new().cpu(service as u64).build();
+                (pid, Process::new(pid, Arc::new(job), Ticks(clock as u64)))
+            })
+            .collect()
+    }
+    pub fn predict(&self, discipline: Discipline) -> QueueMeans {
+        let utilization = self.utilization();
+        assert!(
+            utilization < 1.0,
+            "unstable queue, utilization {:.2}",
+            utilization
+        );
+        let response = match discipline {
+            Discipline::FirstComeFirstServe => {
+                let waiting = self.arrival_rate * self.service.second_moment(self.mean_service)
+                    / (2.0 * (1.0 - utilization));
+                waiting + self.mean_service
+            }
+            Discipline::ProcessorSharing => self.mean_service / (1.0 - utilization),
+        };
+        QueueMeans {
+            waiting: response - self.mean_service,
+            response,
+            in_system: self.arrival_rate * response,
+        }
+    }
+    pub fn run(&self, discipline: Discipline, seed: u64) -> Os {
+        let mut os = Os::new(self.workload(seed), discipline.scheduler(), self.desc());
+        os.run();
+        os
+    }
+    /// Predicted and measured means of every discipline, with the relative errors.
+    pub fn validation_report(&self, seed: u64) -> Report {
+        let mut report = Report::new(&[
+            "Queue",
+            "Scheduler",
+            "ρ",
+            "Waiting",
+            "Response",
+            "In System",
+        ])
+        .align_right(1);
+        for discipline in Discipline::ALL.iter() {
+            let predicted = self.predict(*discipline);
+            let os = self.run(*discipline, seed);
+            let measured = QueueMeans::measure(&os);
+            let cell = |predicted: f64, measured: f64| {
+                format!(
+                    "{:.1} / {:.1} ({:+.1}%)",
+                    predicted,
+                    measured,
+                    (measured - predicted) * 100.0 / predicted
+                )
+            };
+            report.add_row(vec![
+                self.desc().to_string(),
+                os.scheduler_desc().to_string(),
+                format!("{:.2}", self.utilization()),
+                cell(predicted.waiting, measured.waiting),
+                cell(predicted.response, measured.response),
+                cell(predicted.in_system, measured.in_system),
+            ]);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(predicted: f64, measured: f64, tolerance: f64) {
+        let error = (measured - predicted).abs() / predicted;
+        assert!(
+            error < tolerance,
+            "predicted {:.2}, measured {:.2}",
+            predicted,
+            measured
+        );
+    }
+
+    #[test]
+    fn measured_means_agree_with_formulas() {
+        for &service in [ServiceTime::Exponential, ServiceTime::Deterministic].iter() {
+            let model = QueueModel::mg1(0.025, 20.0, service).jobs(3000);
+            for &discipline in Discipline::ALL.iter() {
+                let predicted = model.predict(discipline);
+                let measured = QueueMeans::measure(&model.run(discipline, 42));
+                assert_close(predicted.response, measured.response, 0.15);
+                assert_close(predicted.in_system, measured.in_system, 0.15);
+            }
+        }
+    }
+}
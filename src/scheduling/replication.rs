@@ -0,0 +1,425 @@
+//! Replications
+//!
+//! Runs every scheduler over N seeded replications of a workload,
+//! summarizes each metric by its mean, standard deviation and 95% confidence interval,
+//! and tests whether the differences between schedulers are significant.
+//!
+//! All schedulers see the same seeds, so they are compared by a paired t-test
+//! on the per replication differences.
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::scheduling::report::Report;
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Os, PId, Process, Scheduler};
+
+pub type Workload = Arc<dyn Fn(u64) -> IndexMap<PId, Process> + Send + Sync>;
+pub type SchedulerFactory = Arc<dyn Fn() -> Box<dyn Scheduler + Send> + Send + Sync>;
+/// Builds up the os of every run, for example with a cache or power model.
+pub type Configure = Arc<dyn Fn(Os) -> Os + Send + Sync>;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    Waiting,
+    Admission,
+    TurnAround,
+    WeightedTurnAround,
+    CpuUsage,
+    ContextSwitches,
+    Preemptions,
+    WarmUp,
+    User,
+    System,
+    Energy,
+    Swaps,
+    Suspended,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 13] = [
+        Metric::Waiting,
+        Metric::Admission,
+        Metric::TurnAround,
+        Metric::WeightedTurnAround,
+        Metric::CpuUsage,
+        Metric::ContextSwitches,
+        Metric::Preemptions,
+        Metric::WarmUp,
+        Metric::User,
+        Metric::System,
+        Metric::Energy,
+        Metric::Swaps,
+        Metric::Suspended,
+    ];
+    pub fn title(&self) -> &'static str {
+        match self {
+            Metric::Waiting => "Ave Waiting",
+            Metric::Admission => "Ave Admission",
+            Metric::TurnAround => "Ave Turn Around",
+            Metric::WeightedTurnAround => "Ave Wtd Turn Around",
+            Metric::CpuUsage => "CPU Usage",
+            Metric::ContextSwitches => "Context Switches",
+            Metric::Preemptions => "Preemptions",
+            Metric::WarmUp => "Warm-up",
+            Metric::User => "User",
+            Metric::System => "System",
+            Metric::Energy => "Energy",
+            Metric::Swaps => "Swaps",
+            Metric::Suspended => "Suspended",
+        }
+    }
+    /// Unlike [`Os::stats`], the averages are not truncated to integers.
+    pub fn measure(&self, os: &Os) -> f64 {
+        let processes = os.processes().collect::<Vec<_>>();
+        let mean = |f: &dyn Fn(&Process) -> f64| {
+            processes.iter().map(|p| f(p)).sum::<f64>() / processes.len().max(1) as f64
+        };
+        match self {
+            Metric::Waiting => mean(&|p| p.waiting_time() as f64),
+            Metric::Admission => mean(&|p| p.admission_delay() as f64),
+            Metric::TurnAround => mean(&|p| p.turn_around_time() as f64),
+            Metric::WeightedTurnAround => mean(&|p| {
+                p.turn_around_time() as f64 / p.burst_time().max(1) as f64
+            }),
+            Metric::CpuUsage => {
+                let burst_time = processes.iter().map(|p| p.burst_time()).sum::<u64>();
+                burst_time as f64 * 100.0 / os.clock.since(Ticks::ZERO).max(1) as f64
+            }
+            Metric::ContextSwitches => os.context_switch_times() as f64,
+            Metric::Preemptions => os.preemption_times() as f64,
+            Metric::WarmUp => processes.iter().map(|p| p.warm_up_time() as f64).sum(),
+            Metric::User => processes.iter().map(|p| p.burst_time() as f64).sum(),
+            Metric::System => os.system_time() as f64,
+            Metric::Energy => os.power_usage().energy as f64,
+            Metric::Swaps => {
+                let (swap_ins, swap_outs) = os.swaps();
+                (swap_ins + swap_outs) as f64
+            }
+            Metric::Suspended => processes.iter().map(|p| p.suspended_time() as f64).sum(),
+        }
+    }
+}
+
+/// Two-sided critical values of Student's t-distribution at the 0.05 level,
+/// for 1 to 30 degrees of freedom.
+const T_CRITICAL: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Rounds the degrees of freedom down to the nearest tabulated one, which is conservative.
+pub fn t_critical(degrees_of_freedom: usize) -> f64 {
+    match degrees_of_freedom {
+        0 => f64::INFINITY,
+        1..=30 => T_CRITICAL[degrees_of_freedom - 1],
+        31..=39 => 2.042,
+        40..=59 => 2.021,
+        60..=119 => 2.000,
+        120..=999 => 1.980,
+        _ => 1.960,
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    /// sample standard deviation
+    pub std_dev: f64,
+    /// half width of the 95% confidence interval of the mean
+    pub margin: f64,
+}
+
+impl Summary {
+    pub fn new(samples: &[f64]) -> Self {
+        let count = samples.len();
+        let mean = samples.iter().sum::<f64>() / count.max(1) as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>()
+            / count.saturating_sub(1).max(1) as f64;
+        let std_dev = variance.sqrt();
+        let margin = if count > 1 {
+            t_critical(count - 1) * std_dev / (count as f64).sqrt()
+        } else {
+            f64::INFINITY
+        };
+        Self {
+            count,
+            mean,
+            std_dev,
+            margin,
+        }
+    }
+    /// 95% confidence interval of the mean
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        (self.mean - self.margin, self.mean + self.margin)
+    }
+}
+
+/// Paired t-test at the 0.05 level.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Comparison {
+    /// mean of `a - b`
+    pub mean_diff: f64,
+    pub t: f64,
+    pub is_significant: bool,
+}
+
+impl Comparison {
+    pub fn new(a: &[f64], b: &[f64]) -> Self {
+        let diffs = a.iter().zip(b).map(|(a, b)| a - b).collect::<Vec<_>>();
+        let summary = Summary::new(&diffs);
+        let t = if summary.std_dev > 0.0 {
+            summary.mean / (summary.std_dev / (summary.count as f64).sqrt())
+        } else if summary.mean == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY.copysign(summary.mean)
+        };
+        Self {
+            mean_diff: summary.mean,
+            t,
+            is_significant: summary.count > 1
+                && t.abs() > t_critical(summary.count.saturating_sub(1)),
+        }
+    }
+}
+
+pub struct Replications {
+    desc: String,
+    replications: usize,
+    seed: u64,
+    workload: Workload,
+    configure: Configure,
+    schedulers: Vec<SchedulerFactory>,
+}
+
+impl Replications {
+    /// `workload` builds the processes of a replication from its seed.
+    pub fn new(
+        desc: impl Into<String>,
+        replications: usize,
+        workload: impl Fn(u64) -> IndexMap<PId, Process> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            desc: desc.into(),
+            replications,
+            seed: 0,
+            workload: Arc::new(workload),
+            configure: Arc::new(|os| os),
+            schedulers: vec![],
+        }
+    }
+    /// The seed of the first replication, the following ones increment it.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    /// Configures the os of every run, for example with a model or a long-term scheduler.
+    /// Without one, the metrics it drives, such as [`Metric::WarmUp`], [`Metric::Energy`]
+    /// or [`Metric::Swaps`], are always zero.
+    pub fn configure(mut self, configure: impl Fn(Os) -> Os + Send + Sync + 'static) -> Self {
+        self.configure = Arc::new(configure);
+        self
+    }
+    /// Adds a scheduler, created afresh for every replication.
+    pub fn scheduler(
+        mut self,
+        scheduler: impl Fn() -> Box<dyn Scheduler + Send> + Send + Sync + 'static,
+    ) -> Self {
+        self.schedulers.push(Arc::new(scheduler));
+        self
+    }
+    /// Runs the schedulers in parallel, each over all the replications.
+    pub fn run(self) -> ReplicationReport {
+        let seeds = (0..self.replications as u64)
+            .map(|i| self.seed.wrapping_add(i))
+            .collect::<Vec<_>>();
+        let handles = self
+            .schedulers
+            .iter()
+            .map(|scheduler| {
+                let scheduler = scheduler.clone();
+                let workload = self.workload.clone();
+                let configure = self.configure.clone();
+                let seeds = seeds.clone();
+                let desc = self.desc.clone();
+                std::thread::spawn(move || {
+                    let mut scheduler_desc = "";
+                    let samples = seeds
+                        .into_iter()
+                        .map(|seed| {
+                            let scheduler = scheduler();
+                            scheduler_desc = scheduler.desc();
+                            let mut os =
+                                configure(Os::new(workload(seed), scheduler, desc.as_str()));
+                            os.run();
+                            Metric::ALL
+                                .iter()
+                                .map(|metric| metric.measure(&os))
+                                .collect::<Vec<_>>()
+                        })
+                        .collect::<Vec<_>>();
+                    (scheduler_desc, samples)
+                })
+            })
+            .collect::<Vec<_>>();
+        let (scheduler_descs, samples) = handles
+            .into_iter()
+            .map(|handle| handle.join().expect("failed to run replications"))
+            .unzip();
+        ReplicationReport {
+            desc: self.desc,
+            scheduler_descs,
+            samples,
+        }
+    }
+}
+
+pub struct ReplicationReport {
+    desc: String,
+    scheduler_descs: Vec<&'static str>,
+    /// metric values of every replication of every scheduler
+    samples: Vec<Vec<Vec<f64>>>,
+}
+
+impl ReplicationReport {
+    pub fn samples(&self, scheduler: usize, metric: Metric) -> Vec<f64> {
+        let column = Metric::ALL
+            .iter()
+            .position(|&m| m == metric)
+            .expect("unknown metric");
+        self.samples[scheduler]
+            .iter()
+            .map(|values| values[column])
+            .collect()
+    }
+    pub fn summary(&self, scheduler: usize, metric: Metric) -> Summary {
+        Summary::new(&self.samples(scheduler, metric))
+    }
+    pub fn compare(&self, a: usize, b: usize, metric: Metric) -> Comparison {
+        Comparison::new(&self.samples(a, metric), &self.samples(b, metric))
+    }
+    fn titles(first: &[&'static str]) -> Vec<&'static str> {
+        first
+            .iter()
+            .copied()
+            .chain(Metric::ALL.iter().map(Metric::title))
+            .collect()
+    }
+    /// `mean ± std dev [95% confidence interval]` of every metric.
+    pub fn summary_report(&self) -> Report {
+        let mut report = Report::new(&Self::titles(&["Job", "Scheduler", "Runs"])).align_right(1);
+        for (scheduler, scheduler_desc) in self.scheduler_descs.iter().enumerate() {
+            let mut row = vec![
+                self.desc.clone(),
+                scheduler_desc.to_string(),
+                self.samples[scheduler].len().to_string(),
+            ];
+            for &metric in Metric::ALL.iter() {
+                let summary = self.summary(scheduler, metric);
+                let (low, high) = summary.confidence_interval();
+                row.push(format!(
+                    "{:.1} ± {:.1} [{:.1}, {:.1}]",
+                    summary.mean, summary.std_dev, low, high
+                ));
+            }
+            report.add_row(row);
+        }
+        report
+    }
+    /// Mean difference of every pair of schedulers,
+    /// `*` marks the significant ones and `n.s.` the others.
+    pub fn significance_report(&self) -> Report {
+        let mut report = Report::new(&Self::titles(&["Job", "Scheduler A", "Scheduler B"]))
+            .align_right(1)
+            .align_right(2);
+        for a in 0..self.scheduler_descs.len() {
+            for b in a + 1..self.scheduler_descs.len() {
+                let mut row = vec![
+                    self.desc.clone(),
+                    self.scheduler_descs[a].to_string(),
+                    self.scheduler_descs[b].to_string(),
+                ];
+                for &metric in Metric::ALL.iter() {
+                    let comparison = self.compare(a, b, metric);
+                    row.push(if comparison.is_significant {
+                        format!("{:+.1} *", comparison.mean_diff)
+                    } else {
+                        format!("{:+.1} n.s.", comparison.mean_diff)
+                    });
+                }
+                report.add_row(row);
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::cache::CacheModel;
+    use crate::scheduling::interrupt::InterruptModel;
+    use crate::scheduling::power::PowerModel;
+    use crate::scheduling::{FirstComeFirstServeScheduler, Job, RoundRobinScheduler};
+
+    #[test]
+    fn summarize_samples() {
+        let summary = Summary::new(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert!((summary.mean - 5.0).abs() < 1e-9);
+        assert!((summary.std_dev - 2.138).abs() < 1e-3);
+        let (low, high) = summary.confidence_interval();
+        assert!((low - 3.212).abs() < 1e-3 && (high - 6.788).abs() < 1e-3);
+    }
+
+    #[test]
+    fn paired_comparison() {
+        let a = [10.0, 12.0, 11.0, 13.0, 12.0];
+        let shifted = a.iter().map(|x| x + 1.0 + x / 100.0).collect::<Vec<_>>();
+        assert!(Comparison::new(&shifted, &a).is_significant);
+        let noisy = [11.0, 11.0, 12.0, 12.0, 12.0];
+        assert!(!Comparison::new(&noisy, &a).is_significant);
+        assert!(!Comparison::new(&a, &a).is_significant);
+    }
+
+    #[test]
+    fn configured_metrics() {
+        let workload = |seed: u64| {
+            (0..3)
+                .map(|pid| {
+                    let job = Arc::new(Job::cpu_bound(10 + seed % 3 + pid as u64));
+                    (pid, Process::new(pid, job, Ticks::ZERO))
+                })
+                .collect()
+        };
+        let replications = || {
+            Replications::new("configured", 3, workload)
+                .scheduler(|| Box::new(FirstComeFirstServeScheduler::new()))
+                .scheduler(|| Box::new(RoundRobinScheduler::new(4)))
+        };
+        let report = replications().run();
+        assert_eq!(report.summary(1, Metric::WarmUp).mean, 0.0);
+        assert_eq!(report.summary(1, Metric::Energy).mean, 0.0);
+        assert_eq!(report.summary(1, Metric::System).mean, 0.0);
+        let report = replications()
+            .configure(|os| {
+                os.with_cache_model(CacheModel::new(2, 1))
+                    .with_power_model(PowerModel::new(1000, 100))
+                    .with_interrupts(InterruptModel::new().timer(10, 1))
+            })
+            .run();
+        // FCFS only warms up each process once, RR after every quantum
+        let (fcfs, rr) = (
+            report.summary(0, Metric::WarmUp),
+            report.summary(1, Metric::WarmUp),
+        );
+        assert_eq!(fcfs.mean, 6.0);
+        assert!(rr.mean > fcfs.mean);
+        assert!(report.summary(0, Metric::Energy).mean > 0.0);
+        assert!(report.summary(0, Metric::System).mean > 0.0);
+        assert_eq!(report.summary(0, Metric::Swaps).mean, 0.0);
+        assert!(report.compare(1, 0, Metric::WarmUp).mean_diff > 0.0);
+    }
+}
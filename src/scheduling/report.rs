@@ -0,0 +1,270 @@
+//! Report Exporters
+//!
+//! The stats tables of [`Os`](crate::scheduling::Os) are built as plain [`Report`]s first,
+//! which can be printed as prettytables or exported as Markdown, CSV and standalone HTML.
+use prettytable::format::Alignment;
+use prettytable::{Cell, Row};
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub titles: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// columns which are aligned to the right
+    pub right_aligned: Vec<usize>,
+}
+
+impl Report {
+    pub fn new(titles: &[&str]) -> Self {
+        Self {
+            titles: titles.iter().map(|title| title.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+    pub fn align_right(mut self, column: usize) -> Self {
+        self.right_aligned.push(column);
+        self
+    }
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+    pub fn is_right_aligned(&self, column: usize) -> bool {
+        self.right_aligned.contains(&column)
+    }
+    pub fn title_row(&self) -> Row {
+        Row::new(
+            self.titles
+                .iter()
+                .map(|title| Cell::new(title).style_spec("Fgb"))
+                .collect(),
+        )
+    }
+    pub fn table_row(&self, row: &[String]) -> Row {
+        Row::new(
+            row.iter()
+                .enumerate()
+                .map(|(column, cell)| {
+                    if self.is_right_aligned(column) {
+                        Cell::new_align(cell, Alignment::RIGHT)
+                    } else {
+                        Cell::new(cell)
+                    }
+                })
+                .collect(),
+        )
+    }
+    pub fn to_table(&self) -> prettytable::Table {
+        let mut table = prettytable::Table::new();
+        crate::utils::set_table_format(&mut table);
+        table.set_titles(self.title_row());
+        for row in self.rows.iter() {
+            table.add_row(self.table_row(row));
+        }
+        table
+    }
+    pub fn export(&self, exporter: &dyn Exporter) -> String {
+        exporter.export(std::slice::from_ref(self))
+    }
+}
+
+pub trait Exporter {
+    /// Renders the reports into one document.
+    fn export(&self, reports: &[Report]) -> String;
+}
+
+/// GitHub flavored Markdown tables.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Markdown;
+
+impl Markdown {
+    /// A line break would end the row, so it becomes `<br>`.
+    fn escape(cell: &str) -> String {
+        cell.replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace(['\r', '\n'], "<br>")
+    }
+}
+
+impl Exporter for Markdown {
+    fn export(&self, reports: &[Report]) -> String {
+        let mut tables = vec![];
+        for report in reports {
+            let mut lines = vec![];
+            let titles = report
+                .titles
+                .iter()
+                .map(|title| Self::escape(title))
+                .collect::<Vec<_>>();
+            lines.push(format!("| {} |", titles.join(" | ")));
+            let separators = (0..report.titles.len())
+                .map(|column| {
+                    if report.is_right_aligned(column) {
+                        "---:"
+                    } else {
+                        "---"
+                    }
+                })
+                .collect::<Vec<_>>();
+            lines.push(format!("| {} |", separators.join(" | ")));
+            for row in report.rows.iter() {
+                let cells = row.iter().map(|cell| Self::escape(cell)).collect::<Vec<_>>();
+                lines.push(format!("| {} |", cells.join(" | ")));
+            }
+            tables.push(lines.join("\n"));
+        }
+        tables.join("\n\n") + "\n"
+    }
+}
+
+/// Comma separated values, reports are separated by an empty line.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Csv;
+
+impl Csv {
+    fn escape(cell: &str) -> String {
+        if cell.contains([',', '"', '\r', '\n']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_string()
+        }
+    }
+    fn line(cells: &[String]) -> String {
+        cells
+            .iter()
+            .map(|cell| Self::escape(cell))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl Exporter for Csv {
+    fn export(&self, reports: &[Report]) -> String {
+        let mut tables = vec![];
+        for report in reports {
+            let mut lines = vec![Self::line(&report.titles)];
+            lines.extend(report.rows.iter().map(|row| Self::line(row)));
+            tables.push(lines.join("\n"));
+        }
+        tables.join("\n\n") + "\n"
+    }
+}
+
+/// A standalone HTML document.
+#[derive(Debug, Clone)]
+pub struct Html {
+    pub title: String,
+}
+
+impl Html {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+        }
+    }
+    fn escape(cell: &str) -> String {
+        cell.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self::new("Scheduling Report")
+    }
+}
+
+impl Exporter for Html {
+    fn export(&self, reports: &[Report]) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", Self::escape(&self.title)));
+        html.push_str(
+            "<style>\n\
+             body { font-family: sans-serif; }\n\
+             table { border-collapse: collapse; margin-bottom: 2em; }\n\
+             th, td { border: 1px solid #ccc; padding: 4px 8px; }\n\
+             th { background: #eee; }\n\
+             .right { text-align: right; }\n\
+             </style>\n",
+        );
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!("<h1>{}</h1>\n", Self::escape(&self.title)));
+        for report in reports {
+            html.push_str("<table>\n<tr>");
+            for title in report.titles.iter() {
+                html.push_str(&format!("<th>{}</th>", Self::escape(title)));
+            }
+            html.push_str("</tr>\n");
+            for row in report.rows.iter() {
+                html.push_str("<tr>");
+                for (column, cell) in row.iter().enumerate() {
+                    if report.is_right_aligned(column) {
+                        html.push_str(&format!("<td class=\"right\">{}</td>", Self::escape(cell)));
+                    } else {
+                        html.push_str(&format!("<td>{}</td>", Self::escape(cell)));
+                    }
+                }
+                html.push_str("</tr>\n");
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let mut report = Report::new(&["Job", "Scheduler", "Ave Waiting"]).align_right(1);
+        report.add_row(vec![
+            "CPU Bound".to_string(),
+            "RR, q=4 | \"fair\"".to_string(),
+            "7(-12%)".to_string(),
+        ]);
+        report
+    }
+
+    #[test]
+    fn export_markdown() {
+        assert_eq!(
+            report().export(&Markdown),
+            "| Job | Scheduler | Ave Waiting |\n\
+             | --- | ---: | --- |\n\
+             | CPU Bound | RR, q=4 \\| \"fair\" | 7(-12%) |\n"
+        );
+    }
+
+    #[test]
+    fn export_csv() {
+        assert_eq!(
+            report().export(&Csv),
+            "Job,Scheduler,Ave Waiting\n\
+             CPU Bound,\"RR, q=4 | \"\"fair\"\"\",7(-12%)\n"
+        );
+    }
+
+    #[test]
+    fn escape_line_breaks() {
+        let mut report = Report::new(&["Job"]);
+        report.add_row(vec!["CPU\r\nBound\rMix\nIO".to_string()]);
+        assert_eq!(
+            report.export(&Markdown),
+            "| Job |\n| --- |\n| CPU<br>Bound<br>Mix<br>IO |\n"
+        );
+        assert_eq!(
+            report.export(&Csv),
+            "Job\n\"CPU\r\nBound\rMix\nIO\"\n"
+        );
+    }
+
+    #[test]
+    fn export_html() {
+        let html = report().export(&Html::default());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<td class=\"right\">RR, q=4 | &quot;fair&quot;</td>"));
+    }
+}
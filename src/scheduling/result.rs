@@ -0,0 +1,180 @@
+//! Run Results
+//!
+//! A [`RunResult`] keeps what is worth comparing of a finished [`Os`] run, without the scheduler,
+//! so runs can be archived and compared with later ones.
+//! With the `serialize` feature, results and workloads are saved and loaded as JSON.
+use indexmap::IndexMap;
+
+use crate::scheduling::os::OsStats;
+use crate::scheduling::report::Report;
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Os, PId, Process};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessMetrics {
+    pub pid: PId,
+    pub job_type: String,
+    pub arrival_time: Ticks,
+    pub completion_time: Ticks,
+    pub burst_time: u64,
+    pub system_time: u64,
+    pub warm_up_time: u64,
+    pub waiting_time: u64,
+    pub turn_around_time: u64,
+    pub weighted_turn_around_time: u64,
+    pub response_times: Vec<u64>,
+}
+
+impl From<&Process> for ProcessMetrics {
+    fn from(p: &Process) -> Self {
+        Self {
+            pid: p.id,
+            job_type: p.job.type_hint().to_string(),
+            arrival_time: p.arrival_time(),
+            completion_time: p.completion_time(),
+            burst_time: p.burst_time(),
+            system_time: p.system_time(),
+            warm_up_time: p.warm_up_time(),
+            waiting_time: p.waiting_time(),
+            turn_around_time: p.turn_around_time(),
+            weighted_turn_around_time: p.weighted_turn_around_time(),
+            response_times: p.response_times().to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunResult {
+    pub jobs_desc: String,
+    pub scheduler_desc: String,
+    /// settings of the run, such as the quantum or the seed
+    pub parameters: IndexMap<String, String>,
+    pub processes: Vec<ProcessMetrics>,
+    pub stats: OsStats,
+}
+
+impl RunResult {
+    /// Takes the cache, interrupt and power models and the long and medium-term schedulers
+    /// of the run as parameters.
+    pub fn new(os: &Os) -> Self {
+        let mut parameters = IndexMap::new();
+        if let Some(cache_model) = os.cache_model() {
+            parameters.insert("cache model".to_string(), format!("{:?}", cache_model));
+        }
+        if let Some(interrupt_model) = os.interrupt_model() {
+            parameters.insert("interrupts".to_string(), format!("{:?}", interrupt_model));
+        }
+        if let Some(power_model) = os.power_model() {
+            parameters.insert("power model".to_string(), format!("{:?}", power_model));
+        }
+        if let Some(long_term) = os.long_term() {
+            parameters.insert("long term".to_string(), format!("{:?}", long_term));
+        }
+        if let Some(medium_term) = os.medium_term() {
+            parameters.insert("medium term".to_string(), format!("{:?}", medium_term));
+        }
+        Self {
+            jobs_desc: os.jobs_desc().to_string(),
+            scheduler_desc: os.scheduler_desc().to_string(),
+            parameters,
+            processes: os.processes().map(ProcessMetrics::from).collect(),
+            stats: os.stats(),
+        }
+    }
+    pub fn parameter(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.parameters.insert(name.into(), value.to_string());
+        self
+    }
+    /// Same as [`Os::os_list_stats_report`], for results which may come from different commits.
+    pub fn stats_report(results: &[RunResult]) -> Report {
+        let mut report = Report::new(&Os::TOTALLED_STATS_TITLES).align_right(1);
+        let stats_list = results.iter().map(|r| r.stats).collect::<Vec<_>>();
+        let average_stats = OsStats::average_stats(&stats_list);
+        for result in results {
+            report.add_row(result.stats.cells(
+                &result.jobs_desc,
+                &result.scheduler_desc,
+                Some(average_stats),
+            ));
+        }
+        report
+    }
+    pub fn parameters_report(results: &[RunResult]) -> Report {
+        let mut report = Report::new(&["Job", "Scheduler", "Parameters"]).align_right(1);
+        for result in results {
+            let parameters = result
+                .parameters
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>();
+            report.add_row(vec![
+                result.jobs_desc.clone(),
+                result.scheduler_desc.clone(),
+                parameters.join(", "),
+            ]);
+        }
+        report
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl RunResult {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json()?)
+    }
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(Self::from_json(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+/// A workload saved as JSON runs again exactly, as long as the processes have not run yet.
+#[cfg(feature = "serialize")]
+pub fn workload_to_json(processes: &IndexMap<PId, Process>) -> serde_json::Result<String> {
+    serde_json::to_string(processes)
+}
+
+#[cfg(feature = "serialize")]
+pub fn workload_from_json(json: &str) -> serde_json::Result<IndexMap<PId, Process>> {
+    serde_json::from_str(json)
+}
+
+#[cfg(all(test, feature = "serialize"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::RoundRobinScheduler;
+
+    #[test]
+    fn round_trip_through_json() {
+        let processes = (0..4)
+            .map(|pid| {
+                let job = JobBuilder::new()
+                    .seed(pid as u64)
+                    .random_bursts(3, 1..20, 1..30)
+                    .build();
+                (pid, Process::new(pid, Arc::new(job), Ticks(pid as u64 * 5)))
+            })
+            .collect::<IndexMap<_, _>>();
+        let processes = workload_from_json(&workload_to_json(&processes).unwrap()).unwrap();
+        let mut os = Os::new(processes, Box::new(RoundRobinScheduler::new(4)), "json");
+        os.run();
+        let result = RunResult::new(&os).parameter("quantum", 4);
+        let reloaded = RunResult::from_json(&result.to_json().unwrap()).unwrap();
+        assert_eq!(reloaded, result);
+        assert_eq!(reloaded.parameters["quantum"], "4");
+        assert_eq!(
+            reloaded.stats.context_switch_times,
+            os.stats().context_switch_times
+        );
+    }
+}
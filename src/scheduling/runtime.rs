@@ -0,0 +1,350 @@
+//! Green Threads
+//!
+//! A user-space cooperative runtime in which every task is a real Rust closure.
+//! Each call of the closure runs the task until its next yield point and takes one tick,
+//! any [`Scheduler`] picks which task resumes next.
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
+
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Job, Os, PId, Process, Scheduler, TICK};
+
+/// What a task does at its yield point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Yield {
+    /// gives up the CPU voluntarily and stays ready
+    Continue,
+    /// blocks for the given ticks, like an I/O statement
+    Block(u64),
+    /// the task has finished
+    Done,
+}
+
+pub type TaskBody = Box<dyn FnMut() -> Yield + Send>;
+
+struct Task {
+    name: String,
+    arrival_time: Ticks,
+    body: TaskBody,
+}
+
+/// Wall-clock measurements of a task.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TaskLatency {
+    /// time from the start of the run until the task was resumed for the first time
+    pub response: Option<Duration>,
+    /// time from the start of the run until the task finished
+    pub completion: Option<Duration>,
+    /// time spent inside the task's closure
+    pub running: Duration,
+    pub resumes: u64,
+}
+
+pub struct Runtime {
+    scheduler: Box<dyn Scheduler + Send>,
+    tasks: IndexMap<PId, Task>,
+    is_explained: bool,
+}
+
+impl Runtime {
+    pub fn new(scheduler: Box<dyn Scheduler + Send>) -> Self {
+        Self {
+            scheduler,
+            tasks: IndexMap::new(),
+            is_explained: false,
+        }
+    }
+    /// Keeps the explanation of every dispatch, see [`Os::with_explanations`].
+    pub fn with_explanations(mut self) -> Self {
+        self.is_explained = true;
+        self
+    }
+    pub fn spawn(
+        &mut self,
+        name: impl Into<String>,
+        body: impl FnMut() -> Yield + Send + 'static,
+    ) -> PId {
+        self.spawn_at(Ticks::ZERO, name, body)
+    }
+    /// Spawns a task which becomes ready at `arrival_time`.
+    pub fn spawn_at(
+        &mut self,
+        arrival_time: Ticks,
+        name: impl Into<String>,
+        body: impl FnMut() -> Yield + Send + 'static,
+    ) -> PId {
+        let pid = self.tasks.len();
+        self.tasks.insert(
+            pid,
+            Task {
+                name: name.into(),
+                arrival_time,
+                body: Box::new(body),
+            },
+        );
+        pid
+    }
+    pub fn run(self) -> RuntimeReport {
+        let Self {
+            scheduler,
+            tasks,
+            is_explained,
+        } = self;
+        // tasks are not described by statements, the job only carries the type
+        let job = Arc::new(Job::cpu_bound(0));
+        let processes = tasks
+            .iter()
+            .map(|(&pid, task)| (pid, Process::new(pid, job.clone(), task.arrival_time)))
+            .collect();
+        let names = tasks
+            .iter()
+            .map(|(&pid, task)| (pid, task.name.clone()))
+            .collect();
+        let latencies = Arc::new(Mutex::new(IndexMap::new()));
+        let green_scheduler = GreenScheduler {
+            inner: scheduler,
+            tasks,
+            started_at: Instant::now(),
+            latencies: latencies.clone(),
+        };
+        let mut os = Os::new(processes, Box::new(green_scheduler), "Green Threads");
+        if is_explained {
+            os = os.with_explanations();
+        }
+        os.run();
+        let latencies = latencies.lock().expect("lock failed").clone();
+        RuntimeReport {
+            os,
+            names,
+            latencies,
+        }
+    }
+}
+
+/// Resumes the closures of the tasks in place of bursting their statements.
+struct GreenScheduler {
+    inner: Box<dyn Scheduler + Send>,
+    tasks: IndexMap<PId, Task>,
+    started_at: Instant,
+    latencies: Arc<Mutex<IndexMap<PId, TaskLatency>>>,
+}
+
+impl GreenScheduler {
+    fn resume(&mut self, pid: PId) -> Yield {
+        let resumed_at = Instant::now();
+        let result = self
+            .tasks
+            .get_mut(&pid)
+            .map_or(Yield::Done, |task| (task.body)());
+        let mut latencies = self.latencies.lock().expect("lock failed");
+        let latency = latencies.entry(pid).or_default();
+        latency
+            .response
+            .get_or_insert_with(|| resumed_at - self.started_at);
+        latency.running += resumed_at.elapsed();
+        latency.resumes += 1;
+        if result == Yield::Done {
+            latency.completion = Some(self.started_at.elapsed());
+        }
+        result
+    }
+}
+
+impl Scheduler for GreenScheduler {
+    fn on_process_ready(&mut self, os: &mut Os, pid: PId) {
+        self.inner.on_process_ready(os, pid);
+    }
+    fn switch_process(&mut self, os: &mut Os) {
+        self.inner.switch_process(os);
+    }
+    fn desc(&self) -> &'static str {
+        self.inner.desc()
+    }
+    fn explain(&self, os: &Os) -> Option<Explanation> {
+        self.inner.explain(os)
+    }
+    fn explain_key(&self, os: &Os, pid: PId) -> Option<Key> {
+        self.inner.explain_key(os, pid)
+    }
+    fn burst_process(&mut self, os: &mut Os) {
+        if os.running_process().is_none() {
+            self.switch_process(os);
+        }
+        os.clock += TICK;
+        let clock = os.clock;
+        let pid = match os.running_process() {
+            Some(process) => {
+                process.account_burst();
+                process.id
+            }
+            None => {
+                os.record_burst(None);
+                return;
+            }
+        };
+        os.record_burst(Some(pid));
+        match self.resume(pid) {
+            Yield::Continue => {}
+            Yield::Block(ticks) => {
+                log::trace!("Clock[{}]: Task[{}] Blocked for {}", clock, pid, ticks);
+                os.await_process(pid, ticks);
+                self.switch_process(os);
+            }
+            Yield::Done => {
+                if let Some(process) = os.get_mut_process(pid) {
+                    process.complete(clock);
+                }
+                self.tasks.remove(&pid);
+                os.complete_process(pid);
+                self.switch_process(os);
+            }
+        }
+        self.on_process_burst(os, pid);
+    }
+    fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
+        self.inner.on_process_burst(os, pid);
+    }
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        self.inner.should_preempt(os, pid)
+    }
+    fn preempt(&mut self, os: &mut Os) {
+        self.inner.preempt(os);
+    }
+}
+
+pub struct RuntimeReport {
+    os: Os,
+    names: IndexMap<PId, String>,
+    latencies: IndexMap<PId, TaskLatency>,
+}
+
+impl RuntimeReport {
+    pub fn os(&self) -> &Os {
+        &self.os
+    }
+    pub fn latency(&self, pid: PId) -> Option<&TaskLatency> {
+        self.latencies.get(&pid)
+    }
+    pub fn latency_table(&self) -> prettytable::Table {
+        let mut table = prettytable::Table::new();
+        crate::utils::set_table_format(&mut table);
+        table.set_titles(row![
+            Fgb =>
+            "PId",
+            "Task",
+            "Resumes",
+            "Waiting",
+            "Turn Around",
+            "Running",
+            "Response Latency",
+            "Completion Latency"
+        ]);
+        let format_duration = |duration: Option<Duration>| {
+            duration.map_or_else(|| "-".to_string(), |d| format!("{:?}", d))
+        };
+        for (pid, name) in self.names.iter() {
+            let latency = self.latencies.get(pid).copied().unwrap_or_default();
+            let (waiting_time, turn_around_time) = self
+                .os
+                .get_process(*pid)
+                .map_or((0, 0), |p| (p.waiting_time(), p.turn_around_time()));
+            table.add_row(row![
+                pid,
+                name,
+                latency.resumes,
+                waiting_time,
+                turn_around_time,
+                format!("{:?}", latency.running),
+                format_duration(latency.response),
+                format_duration(latency.completion)
+            ]);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::{FirstComeFirstServeScheduler, RoundRobinScheduler};
+
+    type ResumeLog = Arc<Mutex<Vec<&'static str>>>;
+
+    /// A task which logs its name on every resume and yields `yields` in order.
+    fn task(
+        name: &'static str,
+        yields: Vec<Yield>,
+        log: &ResumeLog,
+    ) -> impl FnMut() -> Yield + Send {
+        let log = log.clone();
+        let mut yields = yields.into_iter();
+        move || {
+            log.lock().expect("lock failed").push(name);
+            yields.next().unwrap_or(Yield::Done)
+        }
+    }
+
+    fn run(scheduler: Box<dyn Scheduler + Send>) -> (RuntimeReport, Vec<&'static str>) {
+        let log = ResumeLog::default();
+        let mut runtime = Runtime::new(scheduler);
+        let steps = vec![Yield::Continue, Yield::Continue, Yield::Done];
+        runtime.spawn("a", task("a", steps.clone(), &log));
+        runtime.spawn("b", task("b", steps, &log));
+        runtime.spawn_at(
+            Ticks(1),
+            "io",
+            task("io", vec![Yield::Block(5), Yield::Done], &log),
+        );
+        let report = runtime.run();
+        let order = log.lock().expect("lock failed").clone();
+        (report, order)
+    }
+
+    #[test]
+    fn fcfs_resumes_each_task_until_it_blocks_or_is_done() {
+        let (report, order) = run(Box::new(FirstComeFirstServeScheduler::new()));
+        assert_eq!(order, vec!["a", "a", "a", "b", "b", "b", "io", "io"]);
+        // io blocks at 7 for 5 ticks, with nothing else to run
+        let io = report.os().get_process(2).unwrap();
+        assert_eq!((io.completion_time(), io.burst_time()), (Ticks(13), 2));
+        assert_eq!(io.waiting_time(), 13 - 1 - 2);
+    }
+
+    #[test]
+    fn round_robin_interleaves_closures() {
+        let (report, order) = run(Box::new(RoundRobinScheduler::new(2)));
+        assert_eq!(order, vec!["a", "a", "b", "b", "io", "a", "b", "io"]);
+        let completions = (0..3)
+            .map(|pid| report.os().get_process(pid).unwrap().completion_time())
+            .collect::<Vec<_>>();
+        assert_eq!(completions, vec![Ticks(6), Ticks(7), Ticks(11)]);
+        for pid in 0..3 {
+            let latency = report.latency(pid).unwrap();
+            let (response, completion) = (latency.response.unwrap(), latency.completion.unwrap());
+            assert!(response <= completion);
+            assert!(latency.running <= completion);
+        }
+        let resumes = (0..3)
+            .map(|pid| report.latency(pid).unwrap().resumes)
+            .collect::<Vec<_>>();
+        assert_eq!(resumes, vec![3, 3, 2]);
+    }
+
+    #[test]
+    fn explains_with_the_inner_scheduler() {
+        let mut runtime = Runtime::new(Box::new(RoundRobinScheduler::new(2))).with_explanations();
+        runtime.spawn("a", || Yield::Done);
+        runtime.spawn("b", || Yield::Done);
+        let report = runtime.run();
+        let explanations = report.os().explanations();
+        let chosen = explanations.iter().map(|e| e.chosen).collect::<Vec<_>>();
+        assert_eq!(chosen, vec![Some(0), Some(1)]);
+        assert_eq!(
+            explanations[0].rule,
+            "the head of the ready queue, for a quantum of 2"
+        );
+    }
+}
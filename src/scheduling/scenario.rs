@@ -0,0 +1,236 @@
+//! Declarative Scheduling Exercises
+//!
+//! A [`Scenario`] describes a classic exercise as data: the processes with their arrivals and bursts,
+//! the scheduler under test and the expected outcome.
+//! Running it through [`Os`] diffs the actual dispatch order and metrics against the expected ones.
+//! The [`Os`] can be configured beforehand and is kept in the report for further checks.
+use std::fmt;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Job, Os, PId, Process, Scheduler};
+
+pub mod textbook;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExpectedMetrics {
+    pub waiting_time: u64,
+    pub turn_around_time: u64,
+}
+
+/// Builds up the os of a scenario before it runs.
+pub type Configure = Box<dyn FnOnce(Os) -> Os + Send>;
+
+pub struct Scenario {
+    name: String,
+    scheduler: Box<dyn Scheduler + Send>,
+    processes: IndexMap<PId, Process>,
+    configures: Vec<Configure>,
+    dispatch_order: Option<Vec<PId>>,
+    metrics: IndexMap<PId, ExpectedMetrics>,
+}
+
+impl Scenario {
+    pub fn new(name: impl Into<String>, scheduler: Box<dyn Scheduler + Send>) -> Self {
+        Self {
+            name: name.into(),
+            scheduler,
+            processes: IndexMap::new(),
+            configures: vec![],
+            dispatch_order: None,
+            metrics: IndexMap::new(),
+        }
+    }
+    /// Adds a CPU bound process with a single burst.
+    pub fn process(self, pid: PId, arrival_time: u64, burst_time: u64) -> Self {
+        self.process_with_job(pid, arrival_time, Job::cpu_bound(burst_time))
+    }
+    pub fn process_with_job(mut self, pid: PId, arrival_time: u64, job: Job) -> Self {
+        self.processes
+            .insert(pid, Process::new(pid, Arc::new(job), Ticks(arrival_time)));
+        self
+    }
+    /// Configures the os before it runs, for example with a model or a long-term or
+    /// medium-term scheduler, in the order of the calls.
+    pub fn configure(mut self, configure: impl FnOnce(Os) -> Os + Send + 'static) -> Self {
+        self.configures.push(Box::new(configure));
+        self
+    }
+    /// Expects the processes to be dispatched in this order,
+    /// a process preempted and resumed later appears again.
+    pub fn expect_dispatch_order(mut self, dispatch_order: &[PId]) -> Self {
+        self.dispatch_order = Some(dispatch_order.to_vec());
+        self
+    }
+    pub fn expect(mut self, pid: PId, waiting_time: u64, turn_around_time: u64) -> Self {
+        self.metrics.insert(
+            pid,
+            ExpectedMetrics {
+                waiting_time,
+                turn_around_time,
+            },
+        );
+        self
+    }
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+    pub fn run(self) -> ScenarioReport {
+        let Self {
+            name,
+            scheduler,
+            processes,
+            configures,
+            dispatch_order,
+            metrics,
+        } = self;
+        let os = Os::new(processes, scheduler, name.as_str());
+        let mut os = configures.into_iter().fold(os, |os, configure| configure(os));
+        os.run();
+        let mut mismatches = vec![];
+        if let Some(expected) = dispatch_order.as_ref() {
+            let actual = os.dispatch_order();
+            if expected != &actual {
+                mismatches.push(Mismatch::DispatchOrder {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+        for (&pid, expected) in metrics.iter() {
+            let process = match os.get_process(pid) {
+                Some(process) => process,
+                None => {
+                    mismatches.push(Mismatch::MissingProcess(pid));
+                    continue;
+                }
+            };
+            if process.waiting_time() != expected.waiting_time {
+                mismatches.push(Mismatch::WaitingTime {
+                    pid,
+                    expected: expected.waiting_time,
+                    actual: process.waiting_time(),
+                });
+            }
+            if process.turn_around_time() != expected.turn_around_time {
+                mismatches.push(Mismatch::TurnAroundTime {
+                    pid,
+                    expected: expected.turn_around_time,
+                    actual: process.turn_around_time(),
+                });
+            }
+        }
+        ScenarioReport {
+            name,
+            os,
+            dispatch_order,
+            metrics,
+            mismatches,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    DispatchOrder { expected: Vec<PId>, actual: Vec<PId> },
+    WaitingTime { pid: PId, expected: u64, actual: u64 },
+    TurnAroundTime { pid: PId, expected: u64, actual: u64 },
+    /// metrics were expected for a process that is not part of the scenario
+    MissingProcess(PId),
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::DispatchOrder { expected, actual } => write!(
+                f,
+                "dispatch order: expected {:?}, actual {:?}",
+                expected, actual
+            ),
+            Mismatch::WaitingTime {
+                pid,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Process[{}] waiting time: expected {}, actual {}",
+                pid, expected, actual
+            ),
+            Mismatch::TurnAroundTime {
+                pid,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Process[{}] turn around time: expected {}, actual {}",
+                pid, expected, actual
+            ),
+            Mismatch::MissingProcess(pid) => write!(f, "Process[{}] not found", pid),
+        }
+    }
+}
+
+pub struct ScenarioReport {
+    name: String,
+    os: Os,
+    dispatch_order: Option<Vec<PId>>,
+    metrics: IndexMap<PId, ExpectedMetrics>,
+    mismatches: Vec<Mismatch>,
+}
+
+impl ScenarioReport {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+    pub fn os(&self) -> &Os {
+        &self.os
+    }
+    pub fn into_os(self) -> Os {
+        self.os
+    }
+    pub fn is_passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+    pub fn mismatches(&self) -> &[Mismatch] {
+        self.mismatches.as_slice()
+    }
+    /// Every expectation next to the actual outcome, mismatched rows are highlighted.
+    pub fn diff_table(&self) -> prettytable::Table {
+        let mut table = prettytable::Table::new();
+        crate::utils::set_table_format(&mut table);
+        table.set_titles(row![Fgb => "Item", "Expected", "Actual"]);
+        let mut add_row = |item: String, expected: String, actual: String| {
+            if expected == actual {
+                table.add_row(row![item, expected, actual]);
+            } else {
+                table.add_row(row![Fr => item, expected, actual]);
+            }
+        };
+        if let Some(expected) = self.dispatch_order.as_ref() {
+            add_row(
+                "Dispatch Order".to_string(),
+                format!("{:?}", expected),
+                format!("{:?}", self.os.dispatch_order()),
+            );
+        }
+        for (&pid, expected) in self.metrics.iter() {
+            let process = self.os.get_process(pid);
+            let actual = |metric: fn(&Process) -> u64| {
+                process.map_or_else(|| "-".to_string(), |p| metric(p).to_string())
+            };
+            add_row(
+                format!("Process[{}] Waiting", pid),
+                expected.waiting_time.to_string(),
+                actual(Process::waiting_time),
+            );
+            add_row(
+                format!("Process[{}] Turn Around", pid),
+                expected.turn_around_time.to_string(),
+                actual(Process::turn_around_time),
+            );
+        }
+        table
+    }
+}
@@ -0,0 +1,190 @@
+//! Standard textbook exercises for every scheduler in `scheduling::scheduler`.
+//!
+//! Most of them share the classic four-process workload:
+//!
+//! | Process | Arrival | Burst |
+//! |---------|---------|-------|
+//! | 1       | 0       | 8     |
+//! | 2       | 1       | 4     |
+//! | 3       | 2       | 9     |
+//! | 4       | 3       | 5     |
+//!
+//! Ties are broken by FCFS, and a running process is never preempted by an equal one.
+use crate::scheduling::job::JobBuilder;
+use crate::scheduling::scenario::Scenario;
+use crate::scheduling::{
+    ClassPolicy, FirstComeFirstServeScheduler, HighestResponseRatioNextScheduler,
+    LongestJobFirstScheduler, LongestRemainingJobFirstScheduler, MultilevelFeedbackQueueScheduler,
+    MultilevelQueueScheduler, RoundRobinScheduler, Scheduler, ShortestJobFirstScheduler,
+    ShortestRemainingJobFirstScheduler,
+};
+
+fn classic(name: &str, scheduler: Box<dyn Scheduler + Send>) -> Scenario {
+    Scenario::new(name, scheduler)
+        .process(1, 0, 8)
+        .process(2, 1, 4)
+        .process(3, 2, 9)
+        .process(4, 3, 5)
+}
+
+pub fn first_come_first_serve() -> Scenario {
+    classic("FCFS", Box::new(FirstComeFirstServeScheduler::new()))
+        .expect_dispatch_order(&[1, 2, 3, 4])
+        .expect(1, 0, 8)
+        .expect(2, 7, 11)
+        .expect(3, 10, 19)
+        .expect(4, 18, 23)
+}
+
+pub fn shortest_job_first() -> Scenario {
+    classic("SJF", Box::new(ShortestJobFirstScheduler::new()))
+        .expect_dispatch_order(&[1, 2, 4, 3])
+        .expect(1, 0, 8)
+        .expect(2, 7, 11)
+        .expect(3, 15, 24)
+        .expect(4, 9, 14)
+}
+
+/// Process 1 blocks for I/O after 2 ticks and is back before process 2 completes,
+/// with 2 ticks left, shorter than process 3 although its whole job is longer.
+fn with_io(name: &str, scheduler: Box<dyn Scheduler + Send>) -> Scenario {
+    Scenario::new(name, scheduler)
+        .process_with_job(1, 0, JobBuilder::new().cpu(2).io(2).cpu(2).build())
+        .process(2, 1, 3)
+        .process(3, 2, 3)
+}
+
+/// The I/O burst of process 1 counts as waiting.
+pub fn shortest_job_first_with_io() -> Scenario {
+    with_io("SJF with I/O", Box::new(ShortestJobFirstScheduler::new()))
+        .expect_dispatch_order(&[1, 2, 1, 3])
+        .expect(1, 3, 7)
+        .expect(2, 1, 4)
+        .expect(3, 5, 8)
+}
+
+pub fn shortest_remaining_job_first() -> Scenario {
+    classic("SRJF", Box::new(ShortestRemainingJobFirstScheduler::new()))
+        .expect_dispatch_order(&[1, 2, 4, 1, 3])
+        .expect(1, 9, 17)
+        .expect(2, 0, 4)
+        .expect(3, 15, 24)
+        .expect(4, 2, 7)
+}
+
+pub fn longest_job_first() -> Scenario {
+    classic("LJF", Box::new(LongestJobFirstScheduler::new()))
+        .expect_dispatch_order(&[1, 3, 4, 2])
+        .expect(1, 0, 8)
+        .expect(2, 21, 25)
+        .expect(3, 6, 15)
+        .expect(4, 14, 19)
+}
+
+pub fn longest_job_first_with_io() -> Scenario {
+    with_io("LJF with I/O", Box::new(LongestJobFirstScheduler::new()))
+        .expect_dispatch_order(&[1, 2, 3, 1])
+        .expect(1, 6, 10)
+        .expect(2, 1, 4)
+        .expect(3, 3, 6)
+}
+
+pub fn longest_remaining_job_first() -> Scenario {
+    classic("LRJF", Box::new(LongestRemainingJobFirstScheduler::new()))
+        .expect_dispatch_order(&[1, 3, 1, 4, 3, 2, 1, 4, 3, 2, 1, 4, 3, 2, 1, 4, 3])
+        .expect(1, 16, 24)
+        .expect(2, 18, 22)
+        .expect(3, 15, 24)
+        .expect(4, 17, 22)
+}
+
+/// Time quantum 4
+pub fn round_robin() -> Scenario {
+    classic("RR(q=4)", Box::new(RoundRobinScheduler::new(4)))
+        .expect_dispatch_order(&[1, 2, 3, 4, 1, 3, 4, 3])
+        .expect(1, 12, 20)
+        .expect(2, 3, 7)
+        .expect(3, 15, 24)
+        .expect(4, 17, 22)
+}
+
+/// Three queues, time quantum 2 and 4 for the upper two, FCFS for the last one.
+pub fn multilevel_feedback_queue() -> Scenario {
+    classic("MLFQ(q=2,4)", Box::new(MultilevelFeedbackQueueScheduler::new([2, 4])))
+        .expect_dispatch_order(&[1, 2, 3, 4, 1, 2, 3, 4, 1, 3])
+        .expect(1, 15, 23)
+        .expect(2, 9, 13)
+        .expect(3, 15, 24)
+        .expect(4, 13, 18)
+}
+
+/// Processes 2 and 3 are in the foreground queue scheduled by RR with time quantum 2,
+/// 1 and 4 in the background queue scheduled by FCFS, and the foreground queue has strict priority.
+pub fn multilevel_queue() -> Scenario {
+    let scheduler = MultilevelQueueScheduler::new(
+        |p| if p.id == 2 || p.id == 3 { 0 } else { 1 },
+        ClassPolicy::StrictPriority,
+    )
+    .class("Foreground", Box::new(RoundRobinScheduler::new(2)))
+    .class("Background", Box::new(FirstComeFirstServeScheduler::new()));
+    Scenario::new("MLQ(RR q=2/FCFS)", Box::new(scheduler))
+        .process(1, 0, 4)
+        .process(2, 1, 3)
+        .process(3, 2, 3)
+        .process(4, 3, 2)
+        .expect_dispatch_order(&[1, 2, 3, 2, 3, 1, 4])
+        .expect(1, 6, 10)
+        .expect(2, 2, 5)
+        .expect(3, 2, 5)
+        .expect(4, 7, 9)
+}
+
+/// Stallings' five-process workload, where response ratios change the order of C, D and E.
+pub fn highest_response_ratio_next() -> Scenario {
+    Scenario::new("HRRN", Box::new(HighestResponseRatioNextScheduler::new()))
+        .process(1, 0, 3)
+        .process(2, 2, 6)
+        .process(3, 4, 4)
+        .process(4, 6, 5)
+        .process(5, 8, 2)
+        .expect_dispatch_order(&[1, 2, 3, 5, 4])
+        .expect(1, 0, 3)
+        .expect(2, 1, 7)
+        .expect(3, 5, 9)
+        .expect(4, 9, 14)
+        .expect(5, 5, 7)
+}
+
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        first_come_first_serve(),
+        shortest_job_first(),
+        shortest_job_first_with_io(),
+        shortest_remaining_job_first(),
+        longest_job_first(),
+        longest_job_first_with_io(),
+        longest_remaining_job_first(),
+        highest_response_ratio_next(),
+        round_robin(),
+        multilevel_feedback_queue(),
+        multilevel_queue(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn textbook_scenarios() {
+        for scenario in scenarios() {
+            let report = scenario.run();
+            assert!(
+                report.is_passed(),
+                "{}: {:?}",
+                report.name(),
+                report.mismatches()
+            );
+        }
+    }
+}
@@ -1,21 +1,29 @@
-use pendulum::Pendulum;
-
+pub use adaptive_rr::{AdaptiveRoundRobinScheduler, QuantumChoice, QuantumLog, QuantumPolicy};
+pub use energy_aware::{EnergyAwareScheduler, FrequencyPolicy};
 pub use fcfs::FirstComeFirstServeScheduler;
 pub use hrrn::HighestResponseRatioNextScheduler;
 pub use ljf::LongestJobFirstScheduler;
 pub use lrjf::LongestRemainingJobFirstScheduler;
 pub use mlfq::MultilevelFeedbackQueueScheduler;
+pub use mlq::{ClassPolicy, MultilevelQueueScheduler};
+pub use o1::O1Scheduler;
+pub use periodic::{PeriodicPolicy, PeriodicScheduler};
 pub use rr::RoundRobinScheduler;
 pub use sjf::ShortestJobFirstScheduler;
 pub use srjf::ShortestRemainingJobFirstScheduler;
 
-use super::{Os, PId, statement::Statement};
+use super::{explanation::{Explanation, Key}, Os, PId, statement::Statement, TICK};
 
+mod adaptive_rr;
+mod energy_aware;
 mod fcfs;
 mod hrrn;
 mod ljf;
 mod lrjf;
 mod mlfq;
+mod mlq;
+pub mod o1;
+mod periodic;
 mod rr;
 mod sjf;
 mod srjf;
@@ -25,19 +33,55 @@ pub trait Scheduler {
     /// FORCED TO SWITCH!!!
     fn switch_process(&mut self, os: &mut Os);
     fn desc(&self) -> &'static str;
+    /// The candidates and the rule of the next `switch_process`, which calls [`Os::explain`]
+    /// before it picks. `None` if the scheduler can not explain itself.
+    #[allow(unused)]
+    fn explain(&self, os: &Os) -> Option<Explanation> {
+        None
+    }
+    /// The key of the running process, which is not among the candidates of [`explain`]
+    /// when it is preempted. `None` if the scheduler can not explain itself.
+    ///
+    /// [`explain`]: Scheduler::explain
+    #[allow(unused)]
+    fn explain_key(&self, os: &Os, pid: PId) -> Option<Key> {
+        None
+    }
     fn on_tick(&mut self, os: &mut Os) {
-        while let Some(pid) = os.waiting.expired_timeout() {
+        while let Some(pid) = os.expired_process() {
             log::trace!("Clock[{}]: Process[{}] Ready", os.clock, pid);
             self.on_process_ready(os, pid);
+            if os.running_process_pid().is_some() && self.should_preempt(os, pid) {
+                log::trace!("Clock[{}]: Process[{}] Preempts", os.clock, pid);
+                self.preempt(os);
+            }
         }
         self.burst_process(os);
     }
+    /// Runs the CPU for one tick, dispatching a ready process first if it is idle.
     fn burst_process(&mut self, os: &mut Os) {
+        if os.running_process().is_none() {
+            self.switch_process(os);
+        }
+        os.clock += TICK;
         let clock = os.clock;
-        if let Some((new_statement, is_completed, pid)) = os
-            .running_process()
-            .map(|process| (process.burst(clock), process.is_completed(), process.id))
-        {
+        if os.run_interrupt_handler() {
+            let pid = os.running_process_pid();
+            os.record_burst(pid);
+            if let Some(pid) = pid {
+                self.on_process_burst(os, pid);
+            }
+            return;
+        }
+        let is_stalled = os.stall_for_power();
+        if let Some((new_statement, is_completed, pid)) = os.running_process().map(|process| {
+            if is_stalled || process.warm_up() {
+                (None, false, process.id)
+            } else {
+                (process.burst(clock), process.is_completed(), process.id)
+            }
+        }) {
+            os.record_burst(Some(pid));
             if let Some(new_statement) = new_statement {
                 log::trace!(
                     "Clock[{}]: Process[{}] New Statement::{:?}",
@@ -54,14 +98,16 @@ pub trait Scheduler {
             }
             self.on_process_burst(os, pid);
         } else {
-            self.switch_process(os);
+            os.record_burst(None);
         }
     }
     /// Run New Statement
     fn run_statement(&mut self, os: &mut Os, statement: Statement, pid: PId) {
         match statement {
             Statement::CpuBound(duration) => self.run_cpu_bound_statement(os, duration, pid),
-            Statement::IoBound(duration) => self.run_io_bound_statement(os, duration, pid),
+            Statement::IoBound(duration) | Statement::Think(duration) => {
+                self.run_io_bound_statement(os, duration, pid)
+            }
         }
     }
     #[allow(unused)]
@@ -93,4 +139,193 @@ pub trait Scheduler {
     /// CHECK THE RUNNING PROCESS BEFORE SWITCH!!!
     #[allow(unused)]
     fn on_process_burst(&mut self, os: &mut Os, pid: PId) {}
+    /// Whether `pid`, which has just become ready, should take the CPU from the running process.
+    /// Usually be Implemented by Preemptive Algorithms.
+    #[allow(unused)]
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        false
+    }
+    /// Puts the running process back to the ready queue and switches.
+    fn preempt(&mut self, os: &mut Os) {
+        if let Some(pid) = os.running_process_pid() {
+            self.switch_process(os);
+            self.on_process_ready(os, pid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::{Job, JobBuilder};
+    use crate::scheduling::os::TimeSlice;
+    use crate::scheduling::scenario::{Scenario, ScenarioReport};
+    use crate::scheduling::timer::Ticks;
+
+    fn run(scenario: Scenario) -> ScenarioReport {
+        let report = scenario.run();
+        assert!(
+            report.is_passed(),
+            "{}: {:?}",
+            report.name(),
+            report.mismatches()
+        );
+        report
+    }
+
+    fn slice(pid: Option<PId>, start: u64, end: u64) -> TimeSlice {
+        TimeSlice {
+            pid,
+            start: Ticks(start),
+            end: Ticks(end),
+        }
+    }
+
+    #[test]
+    fn idle_cpu_dispatches_before_the_tick() {
+        let report = run(
+            Scenario::new("idle", Box::new(FirstComeFirstServeScheduler::new()))
+                .process(0, 3, 2)
+                .process(1, 3, 1)
+                .expect_dispatch_order(&[0, 1])
+                .expect(0, 0, 2)
+                .expect(1, 2, 3),
+        );
+        // the process which arrives at 3 runs the tick [3, 4), the idle ticks before are recorded
+        assert_eq!(
+            report.os().timeline(),
+            [
+                slice(None, 0, 3),
+                slice(Some(0), 3, 5),
+                slice(Some(1), 5, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn completion_is_the_end_of_the_last_tick() {
+        let job = JobBuilder::new().cpu(2).io(3).cpu(1).build();
+        let report = run(Scenario::new(
+            "completion",
+            Box::new(FirstComeFirstServeScheduler::new()),
+        )
+        .process_with_job(0, 0, job)
+        .expect(0, 3, 6));
+        let os = report.os();
+        assert_eq!(
+            os.timeline(),
+            [
+                slice(Some(0), 0, 2),
+                slice(None, 2, 5),
+                slice(Some(0), 5, 6)
+            ]
+        );
+        assert_eq!(os.get_process(0).unwrap().completion_time(), Ticks(6));
+        assert_eq!(os.clock, Ticks(6));
+    }
+
+    /// pid 1 and pid 3 tie on 3 ticks of CPU, pid 1 blocks for far longer than pid 2 runs
+    fn sized_jobs(name: &str, scheduler: Box<dyn Scheduler + Send>) -> Scenario {
+        Scenario::new(name, scheduler)
+            .process(0, 0, 2)
+            .process_with_job(1, 1, JobBuilder::new().cpu(1).io(20).cpu(2).build())
+            .process(2, 1, 4)
+            .process(3, 1, 3)
+    }
+
+    #[test]
+    fn job_first_keys_by_cpu_time_and_breaks_ties_by_arrival() {
+        run(
+            sized_jobs("SJF", Box::new(ShortestJobFirstScheduler::new()))
+                .expect_dispatch_order(&[0, 1, 3, 2, 1]),
+        );
+        run(sized_jobs("LJF", Box::new(LongestJobFirstScheduler::new()))
+            .expect_dispatch_order(&[0, 2, 1, 3, 1]));
+    }
+
+    #[test]
+    fn response_ratios_are_recomputed_at_dispatch() {
+        // at 4 pid 1 is at 7/4 while pid 2 and pid 3 tie at 3/1
+        run(
+            Scenario::new("HRRN", Box::new(HighestResponseRatioNextScheduler::new()))
+                .process(0, 0, 4)
+                .process(1, 1, 4)
+                .process(2, 2, 1)
+                .process(3, 2, 1)
+                .expect_dispatch_order(&[0, 2, 3, 1]),
+        );
+    }
+
+    #[test]
+    fn equal_remaining_time_does_not_preempt() {
+        let report = run(Scenario::new(
+            "SRJF",
+            Box::new(ShortestRemainingJobFirstScheduler::new()),
+        )
+        .process(0, 0, 5)
+        .process(1, 2, 3)
+        .process(2, 3, 1)
+        .expect_dispatch_order(&[0, 2, 0, 1]));
+        assert_eq!(
+            report.os().timeline(),
+            [
+                slice(Some(0), 0, 3),
+                slice(Some(2), 3, 4),
+                slice(Some(0), 4, 6),
+                slice(Some(1), 6, 9)
+            ]
+        );
+        let report = run(
+            Scenario::new("LRJF", Box::new(LongestRemainingJobFirstScheduler::new()))
+                .process(0, 0, 3)
+                .process(1, 1, 2)
+                .process(2, 2, 4)
+                .expect_dispatch_order(&[0, 2, 1, 0, 2]),
+        );
+        assert_eq!(
+            report.os().timeline(),
+            [
+                slice(Some(0), 0, 2),
+                slice(Some(2), 2, 5),
+                slice(Some(1), 5, 7),
+                slice(Some(0), 7, 8),
+                slice(Some(2), 8, 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn time_slice_restarts_on_dispatch() {
+        let scenario = |name: &str, scheduler: Box<dyn Scheduler + Send>| {
+            Scenario::new(name, scheduler)
+                .process_with_job(0, 0, JobBuilder::new().cpu(1).io(2).cpu(3).build())
+                .process_with_job(1, 0, Job::cpu_bound(6))
+        };
+        // pid 0 gets a whole quantum back after its I/O, not the tick left of the first one
+        let report = run(scenario("RR", Box::new(RoundRobinScheduler::new(2))));
+        assert_eq!(
+            report.os().timeline(),
+            [
+                slice(Some(0), 0, 1),
+                slice(Some(1), 1, 5),
+                slice(Some(0), 5, 7),
+                slice(Some(1), 7, 9),
+                slice(Some(0), 9, 10)
+            ]
+        );
+        let report = run(scenario(
+            "MLFQ",
+            Box::new(MultilevelFeedbackQueueScheduler::new([2, 4])),
+        ));
+        assert_eq!(
+            report.os().timeline(),
+            [
+                slice(Some(0), 0, 1),
+                slice(Some(1), 1, 3),
+                slice(Some(0), 3, 5),
+                slice(Some(1), 5, 9),
+                slice(Some(0), 9, 10)
+            ]
+        );
+    }
 }
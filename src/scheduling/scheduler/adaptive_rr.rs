@@ -0,0 +1,222 @@
+//! Adaptive Round Robin
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Os, PId, Scheduler, TICK};
+
+/// How the time quantum is chosen on every dispatch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QuantumPolicy {
+    /// Target latency divided by the number of ready processes,
+    /// so every ready process runs once within the target latency.
+    QueueLength { target_latency: u64 },
+    /// Median remaining burst of the ready processes, the mean of the two middle ones
+    /// for an even count, so about half of them finish their burst within one quantum.
+    MedianBurst,
+}
+
+/// The quantum chosen when a process was dispatched.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QuantumChoice {
+    pub clock: Ticks,
+    pub pid: PId,
+    /// ready processes including the dispatched one
+    pub ready: usize,
+    pub quantum: u64,
+}
+
+pub type QuantumLog = Arc<Mutex<Vec<QuantumChoice>>>;
+
+/// Round Robin whose time quantum is recomputed from the ready queue on every dispatch.
+pub struct AdaptiveRoundRobinScheduler {
+    ready_queue: VecDeque<PId>,
+    policy: QuantumPolicy,
+    min_quantum: u64,
+    quantum: u64,
+    used_time_slice: u64,
+    quantum_log: QuantumLog,
+}
+
+impl AdaptiveRoundRobinScheduler {
+    pub fn new(policy: QuantumPolicy, min_quantum: u64) -> Self {
+        Self {
+            ready_queue: VecDeque::new(),
+            policy,
+            min_quantum: min_quantum.max(TICK),
+            quantum: 0,
+            used_time_slice: 0,
+            quantum_log: Arc::new(Mutex::new(vec![])),
+        }
+    }
+    pub fn by_queue_length(target_latency: u64, min_quantum: u64) -> Self {
+        Self::new(QuantumPolicy::QueueLength { target_latency }, min_quantum)
+    }
+    pub fn by_median_burst(min_quantum: u64) -> Self {
+        Self::new(QuantumPolicy::MedianBurst, min_quantum)
+    }
+    /// Shared log of the chosen quanta, which stays readable after the scheduler is moved into an [`Os`].
+    pub fn quantum_log(&self) -> QuantumLog {
+        self.quantum_log.clone()
+    }
+    /// `ready_queue` still contains the process to dispatch.
+    fn choose_quantum(&self, os: &Os) -> u64 {
+        let quantum = match self.policy {
+            QuantumPolicy::QueueLength { target_latency } => {
+                target_latency / self.ready_queue.len().max(1) as u64
+            }
+            QuantumPolicy::MedianBurst => {
+                let mut bursts = self
+                    .ready_queue
+                    .iter()
+                    .filter_map(|&pid| os.get_process(pid))
+                    .map(|p| p.remaining_burst_time())
+                    .collect::<Vec<_>>();
+                bursts.sort_unstable();
+                let middle = bursts.len() / 2;
+                match bursts.len() {
+                    0 => 0,
+                    len if len % 2 == 0 => (bursts[middle - 1] + bursts[middle]) / 2,
+                    _ => bursts[middle],
+                }
+            }
+        };
+        quantum.max(self.min_quantum)
+    }
+}
+
+impl Scheduler for AdaptiveRoundRobinScheduler {
+    fn on_process_ready(&mut self, _os: &mut Os, pid: PId) {
+        self.ready_queue.push_back(pid);
+    }
+
+    fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
+        if !self.ready_queue.is_empty() {
+            self.quantum = self.choose_quantum(os);
+        }
+        let ready = self.ready_queue.len();
+        let pid = self.ready_queue.pop_front();
+        if let Some(pid) = pid {
+            self.used_time_slice = 0;
+            log::trace!(
+                "Clock[{}]: Process[{}] Quantum {}",
+                os.clock,
+                pid,
+                self.quantum
+            );
+            self.quantum_log
+                .lock()
+                .expect("lock failed")
+                .push(QuantumChoice {
+                    clock: os.clock,
+                    pid,
+                    ready,
+                    quantum: self.quantum,
+                });
+        }
+        os.switch_process(pid);
+    }
+
+    fn desc(&self) -> &'static str {
+        match self.policy {
+            QuantumPolicy::QueueLength { .. } => {
+                "Adaptive Round Robin (Queue Length); Preemptive; for Job or Process"
+            }
+            QuantumPolicy::MedianBurst => {
+                "Adaptive Round Robin (Median Burst); Preemptive; for Job or Process"
+            }
+        }
+    }
+
+    fn explain(&self, os: &Os) -> Option<Explanation> {
+        let candidates = self.ready_queue.iter().enumerate();
+        Some(Explanation::new(
+            format!(
+                "the head of the ready queue, for a quantum of {}",
+                self.choose_quantum(os)
+            ),
+            candidates
+                .map(|(i, &pid)| (pid, Key::Position(i)))
+                .collect(),
+        ))
+    }
+
+    fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
+        if !os.is_process_running(pid) {
+            return;
+        }
+        self.used_time_slice += TICK;
+        if self.used_time_slice >= self.quantum {
+            self.ready_queue.push_back(pid);
+            self.switch_process(os);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::Process;
+
+    fn run(bursts: &[u64], scheduler: AdaptiveRoundRobinScheduler) -> Vec<QuantumChoice> {
+        let quantum_log = scheduler.quantum_log();
+        let processes = bursts
+            .iter()
+            .enumerate()
+            .map(|(pid, &cpu)| {
+                let job = Arc::new(JobBuilder::new().cpu(cpu).build());
+                (pid, Process::new(pid, job, Ticks::ZERO))
+            })
+            .collect();
+        Os::new(processes, Box::new(scheduler), "adaptive").run();
+        let log = quantum_log.lock().expect("lock failed");
+        log.clone()
+    }
+
+    #[test]
+    fn queue_length_divides_target_latency() {
+        let log = run(
+            &[50, 50, 50, 50],
+            AdaptiveRoundRobinScheduler::by_queue_length(40, 15),
+        );
+        // 40 / 4 is clamped to 15 until the processes start to complete at 185
+        assert_eq!(log.len(), 16);
+        assert!(log[..13]
+            .iter()
+            .all(|choice| choice.ready == 4 && choice.quantum == 15));
+        let tail = log[12..]
+            .iter()
+            .map(|choice| (choice.clock.0, choice.pid, choice.ready, choice.quantum))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tail,
+            vec![
+                (180, 0, 4, 15),
+                (185, 1, 3, 15),
+                (190, 2, 2, 20),
+                (195, 3, 1, 40)
+            ]
+        );
+    }
+
+    #[test]
+    fn median_burst_of_the_ready_queue() {
+        let quanta = |min_quantum| {
+            run(
+                &[5, 20, 40],
+                AdaptiveRoundRobinScheduler::by_median_burst(min_quantum),
+            )
+            .iter()
+            .map(|choice| (choice.pid, choice.quantum))
+            .collect::<Vec<_>>()
+        };
+        // the median of 5 20 40, then of 20 40, then 40 alone
+        assert_eq!(quanta(1), vec![(0, 20), (1, 30), (2, 40)]);
+        assert_eq!(quanta(35), vec![(0, 35), (1, 35), (2, 40)]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Energy-Aware Scheduling
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Os, PId, Scheduler, TICK};
+
+/// How the frequency level is chosen, see [`crate::scheduling::power`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrequencyPolicy {
+    /// Always the full speed, to finish the bursts early and sleep longer.
+    RaceToIdle,
+    /// The slowest level which keeps up with the load, to draw less active power.
+    ///
+    /// Every `window` ticks, the busy share of the window times the current speed estimates
+    /// the speed the load needs, and `headroom` percent is added on top.
+    SlowAndSteady { window: u64, headroom: u64 },
+    /// A fixed level, the slowest is 0.
+    Fixed(usize),
+}
+
+/// Wraps another scheduler, which picks the processes, and sets the CPU frequency by a policy.
+///
+/// The frequency only matters for an [`Os`] with a power model.
+pub struct EnergyAwareScheduler {
+    inner: Box<dyn Scheduler + Send>,
+    policy: FrequencyPolicy,
+    window_start: Ticks,
+    busy_time: u64,
+}
+
+impl EnergyAwareScheduler {
+    pub fn new(inner: Box<dyn Scheduler + Send>, policy: FrequencyPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            window_start: Ticks::ZERO,
+            busy_time: 0,
+        }
+    }
+    pub fn slow_and_steady(inner: Box<dyn Scheduler + Send>) -> Self {
+        Self::new(
+            inner,
+            FrequencyPolicy::SlowAndSteady {
+                window: 20,
+                headroom: 25,
+            },
+        )
+    }
+    pub fn policy(&self) -> FrequencyPolicy {
+        self.policy
+    }
+    /// Called on every busy tick, idle ticks only show up as a longer window.
+    fn govern(&mut self, os: &mut Os) {
+        let level = match (self.policy, os.power_model()) {
+            (_, None) => return,
+            (FrequencyPolicy::RaceToIdle, Some(model)) => model.full_speed_level(),
+            (FrequencyPolicy::Fixed(level), Some(_)) => level,
+            (FrequencyPolicy::SlowAndSteady { window, headroom }, Some(model)) => {
+                self.busy_time += TICK;
+                let elapsed = os.clock.since(self.window_start);
+                if elapsed < window {
+                    return;
+                }
+                let speed = os.frequency().map_or(100, |level| level.speed);
+                let needed = self.busy_time * speed * (100 + headroom) / 100 / elapsed;
+                self.window_start = os.clock;
+                self.busy_time = 0;
+                model.level_for(needed)
+            }
+        };
+        os.set_frequency(level);
+    }
+}
+
+impl Scheduler for EnergyAwareScheduler {
+    fn on_process_ready(&mut self, os: &mut Os, pid: PId) {
+        self.inner.on_process_ready(os, pid);
+    }
+    fn switch_process(&mut self, os: &mut Os) {
+        self.inner.switch_process(os);
+    }
+    fn desc(&self) -> &'static str {
+        self.inner.desc()
+    }
+    fn explain(&self, os: &Os) -> Option<Explanation> {
+        self.inner.explain(os)
+    }
+    fn explain_key(&self, os: &Os, pid: PId) -> Option<Key> {
+        self.inner.explain_key(os, pid)
+    }
+    fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
+        self.govern(os);
+        self.inner.on_process_burst(os, pid);
+    }
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        self.inner.should_preempt(os, pid)
+    }
+    fn preempt(&mut self, os: &mut Os) {
+        self.inner.preempt(os);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::power::PowerModel;
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::FirstComeFirstServeScheduler;
+
+    fn run(policy: FrequencyPolicy) -> Os {
+        // busy a fifth of the time
+        let job = JobBuilder::new()
+            .cpu(10)
+            .io(40)
+            .cpu(10)
+            .io(40)
+            .cpu(10)
+            .build();
+        let inner = Box::new(FirstComeFirstServeScheduler::new());
+        let scheduler = Box::new(EnergyAwareScheduler::new(inner, policy));
+        let model = PowerModel::new(1000, 100).level(50, 212).level(25, 114);
+        Scenario::new("energy", scheduler)
+            .process_with_job(0, 0, job)
+            .configure(move |os| os.with_power_model(model))
+            .run()
+            .into_os()
+    }
+
+    #[test]
+    fn slow_and_steady_saves_energy_on_a_light_load() {
+        let race = run(FrequencyPolicy::RaceToIdle);
+        let steady = run(FrequencyPolicy::SlowAndSteady {
+            window: 10,
+            headroom: 25,
+        });
+        assert_eq!(race.power_usage().slowed_time, 0);
+        assert!(steady.power_usage().slowed_time > 0);
+        assert!(steady.power_usage().energy < race.power_usage().energy);
+        assert!(steady.stats().average_turn_around_time > race.stats().average_turn_around_time);
+    }
+}
@@ -1,6 +1,7 @@
 //! First Come First Serve
 use std::collections::VecDeque;
 
+use crate::scheduling::explanation::{Explanation, Key};
 use crate::scheduling::{Os, PId, Scheduler};
 
 /// Simplest scheduling algorithm that schedules according to arrival times of processes.
@@ -25,9 +26,18 @@ impl Scheduler for FirstComeFirstServeScheduler {
         self.ready_queue.push_back(pid);
     }
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         os.switch_process(self.ready_queue.pop_front());
     }
     fn desc(&self) -> &'static str {
         "First Come First Serve; Non-Preemptive; for Job or Process"
     }
+
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let candidates = self.ready_queue.iter().enumerate();
+        Some(Explanation::new(
+            "first come first served, the head of the ready queue",
+            candidates.map(|(i, &pid)| (pid, Key::Position(i))).collect(),
+        ))
+    }
 }
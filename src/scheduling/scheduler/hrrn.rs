@@ -1,35 +1,77 @@
 //! Highest Response Ratio Next
-use keyed_priority_queue::KeyedPriorityQueue;
+use indexmap::IndexMap;
 
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::timer::Ticks;
 use crate::scheduling::{Os, PId, Scheduler};
 
 /// In this scheduling, processes with highest response ratio is scheduled.
 /// This algorithm avoids starvation.
 /// Mode: Non-Preemptive
 /// `Response Ratio = (Waiting Time + Burst time) / Burst time`
+///
+/// Ratios are recomputed at every dispatch, and ties are broken by FCFS.
+/// The burst time is the CPU time left, see [`Process::remaining_time`].
+///
+/// [`Process::remaining_time`]: crate::scheduling::Process::remaining_time
 #[derive(Default, Clone)]
 pub struct HighestResponseRatioNextScheduler {
-    ready_queue: KeyedPriorityQueue<PId, u64>
+    /// pid -> clock when it entered the ready queue
+    ready_queue: IndexMap<PId, Ticks>,
 }
 
 impl HighestResponseRatioNextScheduler {
     pub fn new() -> Self {
         Self::default()
     }
+    /// `(Waiting Time + Burst time, Burst time)` as of the current clock
+    fn response_ratio(os: &Os, pid: PId, ready_since: Ticks) -> (u64, u64) {
+        let burst_time = os
+            .get_process(pid)
+            .map_or(1, |p| p.remaining_time().max(1));
+        (os.clock.since(ready_since) + burst_time, burst_time)
+    }
 }
 
 impl Scheduler for HighestResponseRatioNextScheduler {
     fn on_process_ready(&mut self, os: &mut Os, pid: usize) {
-        if let Some(process) = os.get_process(pid) {
-            self.ready_queue.push(pid, process.job.response_ratio());
-        }
+        self.ready_queue.insert(pid, os.clock);
     }
 
     fn switch_process(&mut self, os: &mut Os) {
-        os.switch_process(self.ready_queue.pop().map(|(pid, _)| pid));
+        os.explain(self);
+        let mut highest: Option<(usize, (u64, u64))> = None;
+        for (index, (&pid, &ready_since)) in self.ready_queue.iter().enumerate() {
+            let (numerator, denominator) = Self::response_ratio(os, pid, ready_since);
+            if highest.map_or(true, |(_, (n, d))| numerator * d > n * denominator) {
+                highest = Some((index, (numerator, denominator)));
+            }
+        }
+        let pid = highest
+            .and_then(|(index, _)| self.ready_queue.shift_remove_index(index))
+            .map(|(pid, _)| pid);
+        os.switch_process(pid);
     }
 
     fn desc(&self) -> &'static str {
         "Highest Response Ratio Next; Non-Preemptive; for Job"
     }
+
+    fn explain(&self, os: &Os) -> Option<Explanation> {
+        let mut candidates = self
+            .ready_queue
+            .iter()
+            .map(|(&pid, &ready_since)| (pid, Self::response_ratio(os, pid, ready_since)))
+            .collect::<Vec<_>>();
+        // stable, so ties stay in arrival order
+        candidates.sort_by(|(_, (n1, d1)), (_, (n2, d2))| (n2 * d1).cmp(&(n1 * d2)));
+        let candidates = candidates.into_iter().map(|(pid, (numerator, burst_time))| {
+            let waiting_time = numerator - burst_time;
+            (pid, Key::ResponseRatio { waiting_time, burst_time })
+        });
+        Some(Explanation::new(
+            "highest response ratio next, ties by arrival",
+            candidates.collect(),
+        ))
+    }
 }
@@ -1,16 +1,26 @@
 //! Longest Job First
 //!
+use std::cmp::Reverse;
+
 use keyed_priority_queue::KeyedPriorityQueue;
 
+use crate::scheduling::explanation::{Explanation, Key};
 use crate::scheduling::{Os, PId, Scheduler};
 
 /// It is similar to SJF scheduling algorithm.
 /// But, in this scheduling algorithm, we give priority to the process having the longest burst time.
 /// This is non-preemptive in nature i.e., when any process starts executing,
 /// can’t be interrupted before complete execution.
+/// If two processes have the same bust time then FCFS is used to break the tie.
+///
+/// The burst time is the CPU time left when the process becomes ready,
+/// see [`Process::remaining_time`].
+///
+/// [`Process::remaining_time`]: crate::scheduling::Process::remaining_time
 #[derive(Default, Clone)]
 pub struct LongestJobFirstScheduler {
-    ready_queue: KeyedPriorityQueue<PId, u64>,
+    ready_queue: KeyedPriorityQueue<PId, (u64, Reverse<usize>)>,
+    ready_count: usize,
 }
 
 impl LongestJobFirstScheduler {
@@ -22,12 +32,15 @@ impl LongestJobFirstScheduler {
 impl Scheduler for LongestJobFirstScheduler {
     fn on_process_ready(&mut self, os: &mut Os, pid: usize) {
         if let Some(process) = os.get_process(pid) {
-            let burst_time = process.burst_time();
-            self.ready_queue.push(pid, burst_time);
+            let burst_time = process.remaining_time();
+            self.ready_queue
+                .push(pid, (burst_time, Reverse(self.ready_count)));
+            self.ready_count += 1;
         }
     }
 
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         os.switch_process(self.ready_queue.pop().map(|(pid, _)| pid));
     }
 
@@ -35,4 +48,13 @@ impl Scheduler for LongestJobFirstScheduler {
         "Longest Job First; Non-Preemptive; for Job"
     }
 
+    /// Pops a copy of the ready queue, so the candidates come in the order they would run.
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let mut ready_queue = self.ready_queue.clone();
+        let mut candidates = vec![];
+        while let Some((pid, (burst_time, _))) = ready_queue.pop() {
+            candidates.push((pid, Key::BurstTime(burst_time)));
+        }
+        Some(Explanation::new("longest burst time first, ties by arrival", candidates))
+    }
 }
@@ -1,29 +1,43 @@
 //! Longest Remaining Job First
 //!
+use std::cmp::Reverse;
+
 use keyed_priority_queue::KeyedPriorityQueue;
 
+use crate::scheduling::explanation::{Explanation, Key};
 use crate::scheduling::{Os, PId, Scheduler};
 
 /// It is preemptive mode of LJF algorithm in which we give priority to the process having largest burst time remaining.
+/// The running process is preempted as soon as another one has more time remaining,
+/// either because it has just become ready or because the running one keeps shrinking.
+/// Ties are broken by FCFS, and the running process is never preempted by an equal one.
 #[derive(Default, Clone)]
 pub struct LongestRemainingJobFirstScheduler {
-    ready_queue: KeyedPriorityQueue<PId, u64>,
+    ready_queue: KeyedPriorityQueue<PId, (u64, Reverse<usize>)>,
+    ready_count: usize,
 }
 
 impl LongestRemainingJobFirstScheduler {
     pub fn new() -> Self {
         Self::default()
     }
+    fn push(&mut self, pid: PId, remaining_time: u64) {
+        self.ready_queue
+            .push(pid, (remaining_time, Reverse(self.ready_count)));
+        self.ready_count += 1;
+    }
 }
 
 impl Scheduler for LongestRemainingJobFirstScheduler {
     fn on_process_ready(&mut self, os: &mut Os, pid: usize) {
         if let Some(process) = os.get_process(pid) {
-            self.ready_queue.push(pid, process.remaining_time());
+            let remaining_time = process.remaining_time();
+            self.push(pid, remaining_time);
         }
     }
 
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         os.switch_process(self.ready_queue.pop().map(|(pid, _)| pid));
     }
 
@@ -31,6 +45,29 @@ impl Scheduler for LongestRemainingJobFirstScheduler {
         "Longest Remaining Job First; Preemptive; for Job"
     }
 
+    /// Pops a copy of the ready queue, so the candidates come in the order they would run.
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let mut ready_queue = self.ready_queue.clone();
+        let mut candidates = vec![];
+        while let Some((pid, (remaining_time, _))) = ready_queue.pop() {
+            candidates.push((pid, Key::RemainingTime(remaining_time)));
+        }
+        Some(Explanation::new("longest remaining time first, ties by arrival", candidates))
+    }
+    fn explain_key(&self, os: &Os, pid: PId) -> Option<Key> {
+        os.get_process(pid)
+            .map(|p| Key::RemainingTime(p.remaining_time()))
+    }
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        let remaining_time = |pid| os.get_process(pid).map(|p| p.remaining_time());
+        match (remaining_time(pid), os.running_process_pid().and_then(remaining_time)) {
+            (Some(ready_remaining_time), Some(running_remaining_time)) => {
+                ready_remaining_time.gt(&running_remaining_time)
+            }
+            _ => false,
+        }
+    }
+
     fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
         if !os.is_process_running(pid) {
             return;
@@ -39,10 +76,10 @@ impl Scheduler for LongestRemainingJobFirstScheduler {
         if self
             .ready_queue
             .peek()
-            .map_or(false, |(_, remaining_time)| remaining_time.gt(&current_remaining_time))
+            .map_or(false, |(_, (remaining_time, _))| remaining_time.gt(&current_remaining_time))
         {
             self.switch_process(os);
-            self.ready_queue.push(pid, current_remaining_time);
+            self.push(pid, current_remaining_time);
         }
     }
 }
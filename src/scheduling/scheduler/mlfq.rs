@@ -3,6 +3,8 @@ use std::collections::HashMap;
 
 use indexmap::IndexSet;
 
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::timer::Ticks;
 use crate::scheduling::{Os, PId, Scheduler, TICK};
 
 #[derive(Default, Clone)]
@@ -36,7 +38,7 @@ impl MultilevelFeedbackQueueScheduler {
         self.running_process
             .map_or(false, |(running_pid, _)| running_pid == pid)
     }
-    pub fn downgrade_process(&mut self, pid: PId, clock: u64) {
+    pub fn downgrade_process(&mut self, pid: PId, clock: Ticks) {
         let priority = self.get_process_priority(pid);
         if priority >= self.ready_queues.len() - 1 {
             return;
@@ -56,13 +58,15 @@ impl Scheduler for MultilevelFeedbackQueueScheduler {
     }
 
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         if let Some((pid, priority)) = self
             .ready_queues
             .iter_mut()
             .enumerate()
-            .find_map(|(priority, queue)| queue.pop().map(|pid| (pid, priority)))
+            .find_map(|(priority, queue)| queue.shift_remove_index(0).map(|pid| (pid, priority)))
         {
             self.running_process = Some((pid, priority));
+            self.used_time_slice_map.insert(pid, 0);
             os.switch_process(Some(pid));
         } else {
             self.running_process = None;
@@ -74,26 +78,42 @@ impl Scheduler for MultilevelFeedbackQueueScheduler {
         "Multilevel Feedback Queue; Preemptive; for Job or Process"
     }
 
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let candidates = self.ready_queues.iter().enumerate().flat_map(|(level, queue)| {
+            queue.iter().enumerate().map(move |(position, &pid)| {
+                (pid, Key::QueueLevel { level, position })
+            })
+        });
+        Some(Explanation::new(
+            "the head of the highest non-empty queue",
+            candidates.collect(),
+        ))
+    }
+
     fn on_process_burst(&mut self, os: &mut Os, pid: usize) {
         let priority = self.get_process_priority(pid);
-        let last_priority = self.last_priority();
-        if priority >= last_priority {
-            if self.ready_queues[0..last_priority]
-                .iter()
-                .any(|q| !q.is_empty())
-            {
-                self.ready_queues[last_priority].insert(pid);
-                self.switch_process(os);
-            }
-        } else {
-            let used_time_slice = self.used_time_slice_map.get(&pid).copied().unwrap_or(0);
-            if used_time_slice >= self.time_slices[priority] && os.is_process_running(pid) {
-                self.downgrade_process(pid, os.clock);
-                self.used_time_slice_map.insert(pid, 0);
-                self.switch_process(os);
-            } else {
-                self.used_time_slice_map.insert(pid, used_time_slice + TICK);
-            }
+        if priority >= self.last_priority() {
+            return;
+        }
+        let used_time_slice = self.used_time_slice_map.get(&pid).copied().unwrap_or(0) + TICK;
+        self.used_time_slice_map.insert(pid, used_time_slice);
+        if used_time_slice >= self.time_slices[priority] && os.is_process_running(pid) {
+            self.downgrade_process(pid, os.clock);
+            self.switch_process(os);
+        }
+    }
+
+    /// Processes become ready in the first queue, which preempts any lower queue.
+    fn should_preempt(&self, _os: &Os, _pid: PId) -> bool {
+        self.running_process
+            .map_or(false, |(_, priority)| priority > 0)
+    }
+
+    /// The preempted process stays in its queue.
+    fn preempt(&mut self, os: &mut Os) {
+        if let Some((pid, priority)) = self.running_process {
+            self.ready_queues[priority].insert(pid);
+            self.switch_process(os);
         }
     }
 }
@@ -0,0 +1,385 @@
+//! Multilevel Queue
+use std::collections::HashMap;
+
+use indexmap::IndexSet;
+
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::{Os, PId, Process, ProcessState, Scheduler, TICK};
+
+/// Maps a process to the index of its class, out of range indexes fall into the last class.
+pub type Classifier = Box<dyn Fn(&Process) -> usize + Send>;
+
+/// How the CPU is shared between the classes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassPolicy {
+    /// A class only runs when every higher class is empty, and is preempted by them.
+    StrictPriority,
+    /// Classes take turns in order, each for its own time slice.
+    TimeSliced(Vec<u64>),
+}
+
+struct Class {
+    name: &'static str,
+    scheduler: Box<dyn Scheduler + Send>,
+    /// processes handed to the scheduler of this class and not dispatched yet
+    ready: IndexSet<PId>,
+    /// the process taken off the CPU by another class, which resumes before the scheduler picks
+    suspended: Option<PId>,
+}
+
+/// Processes are statically classified into classes, for example foreground and background.
+/// Each class has its own ready queue which is scheduled by any existing scheduler,
+/// so hierarchical policies can be assembled from the existing ones.
+pub struct MultilevelQueueScheduler {
+    classes: Vec<Class>,
+    classifier: Classifier,
+    policy: ClassPolicy,
+    class_map: HashMap<PId, usize>,
+    running_class: Option<usize>,
+    /// the class whose turn it is, for `ClassPolicy::TimeSliced`
+    turn: usize,
+    used_time_slice: u64,
+}
+
+impl MultilevelQueueScheduler {
+    /// At least one class must be added with [`class`](Self::class) before the first arrival.
+    pub fn new(classifier: impl Fn(&Process) -> usize + Send + 'static, policy: ClassPolicy) -> Self {
+        Self {
+            classes: vec![],
+            classifier: Box::new(classifier),
+            policy,
+            class_map: HashMap::new(),
+            running_class: None,
+            turn: 0,
+            used_time_slice: 0,
+        }
+    }
+    /// Appends a class, the earlier ones have higher priority.
+    pub fn class(mut self, name: &'static str, scheduler: Box<dyn Scheduler + Send>) -> Self {
+        self.classes.push(Class {
+            name,
+            scheduler,
+            ready: IndexSet::new(),
+            suspended: None,
+        });
+        self
+    }
+    /// I/O bound processes in the foreground class and CPU bound ones in the background class.
+    pub fn foreground_background(
+        foreground: Box<dyn Scheduler + Send>,
+        background: Box<dyn Scheduler + Send>,
+        policy: ClassPolicy,
+    ) -> Self {
+        Self::new(|p| if p.is_io_bound() { 0 } else { 1 }, policy)
+            .class("Foreground", foreground)
+            .class("Background", background)
+    }
+    pub fn class_name(&self, class: usize) -> Option<&'static str> {
+        self.classes.get(class).map(|c| c.name)
+    }
+    /// returns the last class if not classified yet
+    pub fn class_of(&self, pid: PId) -> usize {
+        self.class_map
+            .get(&pid)
+            .copied()
+            .unwrap_or_else(|| self.classes.len().saturating_sub(1))
+    }
+    fn has_ready(&self, class: usize) -> bool {
+        self.classes
+            .get(class)
+            .map_or(false, |class| !class.ready.is_empty())
+    }
+    fn next_class(&self) -> Option<usize> {
+        match &self.policy {
+            ClassPolicy::StrictPriority => (0..self.classes.len()).find(|&c| self.has_ready(c)),
+            ClassPolicy::TimeSliced(time_slices) => {
+                let time_slice = time_slices.get(self.turn).copied().unwrap_or(0);
+                if self.has_ready(self.turn) && self.used_time_slice < time_slice {
+                    return Some(self.turn);
+                }
+                (1..=self.classes.len())
+                    .map(|i| (self.turn + i) % self.classes.len())
+                    .find(|&c| self.has_ready(c))
+            }
+        }
+    }
+    /// Suspends the running process before switching to another class,
+    /// so it resumes at the head of its class instead of rejoining the tail of the ready queue.
+    fn suspend_running(&mut self, os: &Os) {
+        if let Some(pid) = os.running_process_pid() {
+            let class = self.class_of(pid);
+            self.classes[class].suspended = Some(pid);
+            self.classes[class].ready.insert(pid);
+        }
+    }
+    /// Names the class in the rules of the explanations a class scheduler recorded
+    /// since there were `explained` of them.
+    fn name_explanations(&self, os: &mut Os, explained: usize) {
+        for explanation in os.explanations_mut().iter_mut().skip(explained) {
+            if let Some(pid) = explanation.chosen {
+                let name = self.classes[self.class_of(pid)].name;
+                explanation.rule = format!("class {}: {}", name, explanation.rule);
+            }
+        }
+    }
+    /// Keeps the ready sets in sync after a class scheduler may have switched by itself.
+    fn sync(&mut self, os: &Os, before: Option<PId>) {
+        let after = os.running_process_pid();
+        if before == after {
+            return;
+        }
+        if let Some(before) = before {
+            if os
+                .get_process(before)
+                .map_or(false, |p| p.state() == ProcessState::Ready)
+            {
+                let class = self.class_of(before);
+                self.classes[class].ready.insert(before);
+            }
+        }
+        self.running_class = after.map(|pid| {
+            let class = self.class_of(pid);
+            self.classes[class].ready.shift_remove(&pid);
+            class
+        });
+    }
+}
+
+impl Scheduler for MultilevelQueueScheduler {
+    fn on_process_ready(&mut self, os: &mut Os, pid: PId) {
+        assert!(
+            !self.classes.is_empty(),
+            "a multilevel queue needs at least one class, add them with `class`"
+        );
+        let last_class = self.classes.len() - 1;
+        let class = os
+            .get_process(pid)
+            .map_or(last_class, |p| (self.classifier)(p).min(last_class));
+        self.class_map.insert(pid, class);
+        self.classes[class].ready.insert(pid);
+        self.classes[class].scheduler.on_process_ready(os, pid);
+    }
+
+    fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
+        let before = os.running_process_pid();
+        let explained = os.explanations().len();
+        match self.next_class() {
+            Some(class) => {
+                if class != self.turn {
+                    self.turn = class;
+                    self.used_time_slice = 0;
+                }
+                match self.classes[class].suspended.take() {
+                    Some(pid) => os.switch_process(Some(pid)),
+                    None => {
+                        self.classes[class].scheduler.switch_process(os);
+                        self.name_explanations(os, explained);
+                    }
+                }
+            }
+            None => os.switch_process(None),
+        }
+        self.sync(os, before);
+    }
+
+    fn desc(&self) -> &'static str {
+        "Multilevel Queue; Preemptive; for Process"
+    }
+
+    /// The explanation of the class scheduler, with the rule naming the class.
+    fn explain(&self, os: &Os) -> Option<Explanation> {
+        let class = &self.classes[self.next_class()?];
+        if let Some(pid) = class.suspended {
+            return Some(Explanation::new(
+                format!("class {}: the process it suspended resumes", class.name),
+                vec![(pid, Key::Position(0))],
+            ));
+        }
+        let mut explanation = class.scheduler.explain(os)?;
+        explanation.rule = format!("class {}: {}", class.name, explanation.rule);
+        Some(explanation)
+    }
+    fn explain_key(&self, os: &Os, pid: PId) -> Option<Key> {
+        self.classes
+            .get(self.class_of(pid))?
+            .scheduler
+            .explain_key(os, pid)
+    }
+
+    fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
+        let before = os.running_process_pid();
+        let explained = os.explanations().len();
+        let class = self.class_of(pid);
+        self.classes[class].scheduler.on_process_burst(os, pid);
+        self.name_explanations(os, explained);
+        self.sync(os, before);
+        if let ClassPolicy::TimeSliced(time_slices) = &self.policy {
+            if self.running_class != Some(self.turn) {
+                return;
+            }
+            self.used_time_slice += TICK;
+            let time_slice = time_slices.get(self.turn).copied().unwrap_or(0);
+            if self.used_time_slice < time_slice {
+                return;
+            }
+            if (0..self.classes.len()).any(|c| c != self.turn && self.has_ready(c)) {
+                log::trace!(
+                    "Clock[{}]: Class[{}] Time Slice Expired",
+                    os.clock,
+                    self.classes[self.turn].name
+                );
+                self.suspend_running(os);
+                self.switch_process(os);
+            } else {
+                self.used_time_slice = 0;
+            }
+        }
+    }
+
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        let class = self.class_of(pid);
+        match self.running_class {
+            Some(running_class) if running_class == class => {
+                self.classes[class].scheduler.should_preempt(os, pid)
+            }
+            Some(running_class) => self.policy == ClassPolicy::StrictPriority && class < running_class,
+            None => false,
+        }
+    }
+
+    fn preempt(&mut self, os: &mut Os) {
+        let running_class = match self.running_class {
+            Some(running_class) => running_class,
+            None => return,
+        };
+        let is_higher_ready = (0..running_class).any(|c| self.has_ready(c));
+        if self.policy == ClassPolicy::StrictPriority && is_higher_ready {
+            self.suspend_running(os);
+            self.switch_process(os);
+        } else {
+            let before = os.running_process_pid();
+            let explained = os.explanations().len();
+            self.classes[running_class].scheduler.preempt(os);
+            self.name_explanations(os, explained);
+            self.sync(os, before);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::scheduler::{FirstComeFirstServeScheduler, RoundRobinScheduler};
+    use crate::scheduling::job::{Job, JobBuilder};
+    use crate::scheduling::scenario::Scenario;
+
+    fn scenario(scheduler: MultilevelQueueScheduler, jobs: &[(PId, u64, Job)]) -> Scenario {
+        let scenario = Scenario::new("mlq", Box::new(scheduler));
+        jobs.iter().fold(scenario, |scenario, (pid, arrival_time, job)| {
+            scenario.process_with_job(*pid, *arrival_time, job.clone())
+        })
+    }
+
+    fn run(scheduler: MultilevelQueueScheduler, jobs: &[(PId, u64, Job)]) -> Os {
+        scenario(scheduler, jobs).run().into_os()
+    }
+
+    #[test]
+    fn preempted_process_resumes_at_the_head_of_its_class() {
+        let scheduler = MultilevelQueueScheduler::new(
+            |p| if p.id == 2 { 0 } else { 1 },
+            ClassPolicy::StrictPriority,
+        )
+        .class("Foreground", Box::new(FirstComeFirstServeScheduler::new()))
+        .class("Background", Box::new(FirstComeFirstServeScheduler::new()));
+        let os = run(
+            scheduler,
+            &[
+                (1, 0, Job::cpu_bound(5)),
+                (4, 1, Job::cpu_bound(2)),
+                (2, 2, Job::cpu_bound(2)),
+            ],
+        );
+        assert_eq!(os.dispatch_order(), vec![1, 2, 1, 4]);
+    }
+
+    #[test]
+    fn time_sliced_classes_resume_the_suspended_process() {
+        let scheduler = MultilevelQueueScheduler::new(
+            |p| if p.id == 2 { 0 } else { 1 },
+            ClassPolicy::TimeSliced(vec![2, 2]),
+        )
+        .class("Foreground", Box::new(RoundRobinScheduler::new(4)))
+        .class("Background", Box::new(FirstComeFirstServeScheduler::new()));
+        let os = run(
+            scheduler,
+            &[
+                (2, 0, Job::cpu_bound(4)),
+                (1, 0, Job::cpu_bound(3)),
+                (3, 0, Job::cpu_bound(3)),
+            ],
+        );
+        assert_eq!(os.dispatch_order(), vec![2, 1, 2, 1, 3]);
+    }
+
+    #[test]
+    fn explains_by_class() {
+        let scheduler = MultilevelQueueScheduler::new(
+            |p| if p.id == 2 { 0 } else { 1 },
+            ClassPolicy::StrictPriority,
+        )
+        .class("Foreground", Box::new(FirstComeFirstServeScheduler::new()))
+        .class("Background", Box::new(RoundRobinScheduler::new(4)));
+        let jobs = [
+            (1, 0, Job::cpu_bound(5)),
+            (4, 1, Job::cpu_bound(2)),
+            (2, 2, Job::cpu_bound(2)),
+        ];
+        let os = scenario(scheduler, &jobs)
+            .configure(Os::with_explanations)
+            .run()
+            .into_os();
+        let rules = os
+            .explanations()
+            .iter()
+            .map(|e| (e.chosen, e.rule.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                (Some(1), "class Background: the head of the ready queue, for a quantum of 4"),
+                (Some(2), "class Foreground: first come first served, the head of the ready queue"),
+                (Some(1), "class Background: the process it suspended resumes"),
+                (Some(4), "class Background: the head of the ready queue, for a quantum of 4"),
+                (Some(1), "class Background: the head of the ready queue, for a quantum of 4"),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one class")]
+    fn needs_a_class() {
+        let scheduler = MultilevelQueueScheduler::new(|_| 0, ClassPolicy::StrictPriority);
+        run(scheduler, &[(0, 0, Job::cpu_bound(1))]);
+    }
+
+    #[test]
+    fn foreground_background_classifies_by_io() {
+        let scheduler = MultilevelQueueScheduler::foreground_background(
+            Box::new(RoundRobinScheduler::new(2)),
+            Box::new(FirstComeFirstServeScheduler::new()),
+            ClassPolicy::StrictPriority,
+        );
+        let os = run(
+            scheduler,
+            &[
+                (1, 0, Job::cpu_bound(6)),
+                (4, 1, Job::cpu_bound(2)),
+                (2, 2, JobBuilder::new().cpu(1).io(3).cpu(1).build()),
+            ],
+        );
+        assert!(os.get_process(2).unwrap().is_io_bound());
+        assert_eq!(os.dispatch_order(), vec![1, 2, 1, 2, 1, 4]);
+    }
+}
@@ -0,0 +1,356 @@
+//! Linux 2.6 O(1) Scheduler
+use std::collections::{HashMap, VecDeque};
+
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Os, PId, Scheduler, TICK};
+
+/// 0..100 are real-time priorities, 100..140 the nice levels -20..=19.
+pub const MAX_PRIO: usize = 140;
+const MAX_RT_PRIO: usize = 100;
+const NICE_0_PRIO: usize = 120;
+const MAX_BONUS: i64 = 10;
+/// ticks of sleep which earn the full bonus
+const MAX_SLEEP_AVG: u64 = 1000;
+const INTERACTIVE_DELTA: i64 = 2;
+
+/// Priority of a nice level, from 100 for -20 to 139 for 19.
+pub fn static_priority(nice: i8) -> usize {
+    (NICE_0_PRIO as i64 + i64::from(nice.clamp(-20, 19))) as usize
+}
+
+/// 800 ticks for nice -20, 100 for nice 0 and 5 for nice 19.
+pub fn time_slice(static_priority: usize) -> u64 {
+    let scale = if static_priority < NICE_0_PRIO { 20 } else { 5 };
+    (MAX_PRIO - static_priority) as u64 * scale
+}
+
+/// From -5 for a process which never sleeps to +5 for one which sleeps most of the time.
+pub fn bonus(sleep_avg: u64) -> i64 {
+    (sleep_avg.min(MAX_SLEEP_AVG) * MAX_BONUS as u64 / MAX_SLEEP_AVG) as i64 - MAX_BONUS / 2
+}
+
+/// Priorities with a ready process, 140 bits and a queue each.
+#[derive(Clone)]
+struct PriorityArray {
+    bitmap: [u64; 3],
+    queues: Vec<VecDeque<PId>>,
+    len: usize,
+}
+
+impl PriorityArray {
+    fn new() -> Self {
+        Self {
+            bitmap: [0; 3],
+            queues: vec![VecDeque::new(); MAX_PRIO],
+            len: 0,
+        }
+    }
+    fn push(&mut self, pid: PId, priority: usize) {
+        self.queues[priority].push_back(pid);
+        self.bitmap[priority / 64] |= 1 << (priority % 64);
+        self.len += 1;
+    }
+    /// Finds the highest priority by the first set bit, without scanning the queues.
+    fn first_priority(&self) -> Option<usize> {
+        self.bitmap
+            .iter()
+            .enumerate()
+            .find(|(_, &word)| word != 0)
+            .map(|(i, word)| i * 64 + word.trailing_zeros() as usize)
+    }
+    /// Processes in the order they would be popped, with their priorities.
+    fn iter(&self) -> impl Iterator<Item = (PId, usize)> + '_ {
+        self.queues
+            .iter()
+            .enumerate()
+            .flat_map(|(priority, queue)| queue.iter().map(move |&pid| (pid, priority)))
+    }
+    fn pop(&mut self) -> Option<PId> {
+        let priority = self.first_priority()?;
+        let pid = self.queues[priority].pop_front();
+        if self.queues[priority].is_empty() {
+            self.bitmap[priority / 64] &= !(1 << (priority % 64));
+        }
+        self.len -= 1;
+        pid
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Task {
+    static_priority: usize,
+    /// dynamic priority, the static one adjusted by the interactivity bonus
+    priority: usize,
+    time_slice: u64,
+    sleep_avg: u64,
+}
+
+impl Task {
+    fn new(static_priority: usize) -> Self {
+        Self {
+            static_priority,
+            priority: static_priority,
+            time_slice: time_slice(static_priority),
+            sleep_avg: 0,
+        }
+    }
+    fn update_priority(&mut self) {
+        let priority = self.static_priority as i64 - bonus(self.sleep_avg);
+        self.priority = priority.clamp(MAX_RT_PRIO as i64, MAX_PRIO as i64 - 1) as usize;
+    }
+    /// An interactive task earns at least 2 levels of bonus at nice 0,
+    /// more with a higher nice and fewer with a lower one.
+    fn is_interactive(&self) -> bool {
+        let nice = self.static_priority as i64 - NICE_0_PRIO as i64;
+        let delta = nice * MAX_BONUS / 40 + INTERACTIVE_DELTA;
+        bonus(self.sleep_avg) >= delta
+    }
+}
+
+/// Emulates the scheduler of Linux 2.6.0 to 2.6.22.
+///
+/// Ready processes sit in the active array at their dynamic priority, and the scheduler picks
+/// the first one at the highest priority found through the bitmap, in constant time.
+/// A process whose time slice runs out moves to the expired array with a fresh time slice,
+/// and the arrays are swapped once the active one is empty, so every process runs in each epoch.
+///
+/// The dynamic priority rewards processes which sleep a lot: the sleep average grows with sleep
+/// and shrinks with run time, and gives a bonus from -5 to +5. An interactive process goes back
+/// to the active array on expiry, unless the expired processes have starved for too long.
+/// A woken process preempts the running one if its dynamic priority is higher.
+#[derive(Clone)]
+pub struct O1Scheduler {
+    active: PriorityArray,
+    expired: PriorityArray,
+    tasks: HashMap<PId, Task>,
+    nice: HashMap<PId, i8>,
+    running_process: Option<PId>,
+    /// clock when the first process of the current epoch expired
+    first_expired: Option<Ticks>,
+    starvation_limit: u64,
+}
+
+impl O1Scheduler {
+    pub fn new() -> Self {
+        Self {
+            active: PriorityArray::new(),
+            expired: PriorityArray::new(),
+            tasks: HashMap::new(),
+            nice: HashMap::new(),
+            running_process: None,
+            first_expired: None,
+            starvation_limit: MAX_SLEEP_AVG,
+        }
+    }
+    /// Sets the nice level of a process, 0 by default.
+    pub fn nice(mut self, pid: PId, nice: i8) -> Self {
+        self.nice.insert(pid, nice);
+        self
+    }
+    /// How long the expired processes may wait for interactive ones, per ready process.
+    pub fn starvation_limit(mut self, starvation_limit: u64) -> Self {
+        self.starvation_limit = starvation_limit;
+        self
+    }
+    fn task(&mut self, pid: PId) -> &mut Task {
+        let nice = self.nice.get(&pid).copied().unwrap_or(0);
+        self.tasks
+            .entry(pid)
+            .or_insert_with(|| Task::new(static_priority(nice)))
+    }
+    fn is_expired_starving(&self, clock: Ticks) -> bool {
+        let ready = (self.active.len + self.expired.len + 1) as u64;
+        self.first_expired.map_or(false, |first_expired| {
+            clock.since(first_expired) >= self.starvation_limit * ready
+        })
+    }
+}
+
+impl Default for O1Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler for O1Scheduler {
+    fn on_process_ready(&mut self, os: &mut Os, pid: PId) {
+        let clock = os.clock;
+        let slept = os
+            .get_process(pid)
+            .and_then(|p| p.last_ran_at())
+            .map_or(0, |last_ran_at| clock.since(last_ran_at));
+        let task = self.task(pid);
+        task.sleep_avg = (task.sleep_avg + slept).min(MAX_SLEEP_AVG);
+        task.update_priority();
+        // ran out while blocking or completing
+        if task.time_slice == 0 {
+            task.time_slice = time_slice(task.static_priority);
+        }
+        let priority = task.priority;
+        self.active.push(pid, priority);
+    }
+
+    fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
+        if self.active.len == 0 && self.expired.len > 0 {
+            log::trace!("Clock[{}]: Swap Active and Expired Arrays", os.clock);
+            std::mem::swap(&mut self.active, &mut self.expired);
+            self.first_expired = None;
+        }
+        self.running_process = self.active.pop();
+        os.switch_process(self.running_process);
+    }
+
+    fn desc(&self) -> &'static str {
+        "O(1); Preemptive; for Process"
+    }
+
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let (array, rule) = if self.active.len == 0 {
+            (
+                &self.expired,
+                "highest dynamic priority, after swapping the empty active array",
+            )
+        } else {
+            (&self.active, "highest dynamic priority in the active array")
+        };
+        let candidates = array
+            .iter()
+            .map(|(pid, priority)| (pid, Key::Priority(priority)));
+        Some(Explanation::new(rule, candidates.collect()))
+    }
+
+    fn explain_key(&self, _os: &Os, pid: PId) -> Option<Key> {
+        self.tasks
+            .get(&pid)
+            .map(|task| Key::Priority(task.priority))
+    }
+
+    fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
+        let task = self.task(pid);
+        task.sleep_avg = task.sleep_avg.saturating_sub(TICK);
+        task.time_slice = task.time_slice.saturating_sub(TICK);
+        if task.time_slice > 0 || !os.is_process_running(pid) {
+            return;
+        }
+        task.update_priority();
+        task.time_slice = time_slice(task.static_priority);
+        let task = *task;
+        if task.is_interactive() && !self.is_expired_starving(os.clock) {
+            self.active.push(pid, task.priority);
+        } else {
+            self.first_expired.get_or_insert(os.clock);
+            self.expired.push(pid, task.priority);
+        }
+        self.switch_process(os);
+    }
+
+    fn should_preempt(&self, _os: &Os, pid: PId) -> bool {
+        let priority = |pid| self.tasks.get(&pid).map(|task| task.priority);
+        self.running_process
+            .map_or(false, |running_pid| priority(pid) < priority(running_pid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduling::job::JobBuilder;
+    use crate::scheduling::scenario::Scenario;
+
+    fn run(jobs: Vec<(JobBuilder, u64)>, scheduler: O1Scheduler) -> Os {
+        let scenario = Scenario::new("o1", Box::new(scheduler));
+        jobs.into_iter()
+            .enumerate()
+            .fold(scenario, |scenario, (pid, (job, arrival_time))| {
+                scenario.process_with_job(pid, arrival_time, job.build())
+            })
+            .run()
+            .into_os()
+    }
+
+    fn slices(os: &Os) -> Vec<(PId, u64, u64)> {
+        os.timeline()
+            .iter()
+            .filter_map(|slice| slice.pid.map(|pid| (pid, slice.start.0, slice.end.0)))
+            .collect()
+    }
+
+    #[test]
+    fn priorities_and_time_slices() {
+        assert_eq!(static_priority(-20), 100);
+        assert_eq!(static_priority(19), 139);
+        assert_eq!(time_slice(static_priority(-20)), 800);
+        assert_eq!(time_slice(static_priority(0)), 100);
+        assert_eq!(time_slice(static_priority(19)), 5);
+        assert_eq!((bonus(0), bonus(500), bonus(MAX_SLEEP_AVG)), (-5, 0, 5));
+        let mut array = PriorityArray::new();
+        array.push(1, 139);
+        array.push(2, 64);
+        array.push(3, 64);
+        assert_eq!(array.first_priority(), Some(64));
+        assert_eq!(
+            (array.pop(), array.pop(), array.pop()),
+            (Some(2), Some(3), Some(1))
+        );
+        assert_eq!(array.first_priority(), None);
+    }
+
+    #[test]
+    fn sleeper_earns_bonus_and_preempts_hog() {
+        let os = run(
+            vec![
+                (JobBuilder::new().cpu(1000), 0),
+                (JobBuilder::new().cpu(1).io(500).cpu(5), 0),
+            ],
+            O1Scheduler::new(),
+        );
+        // P1 slept 500 ticks, priority 120 against the 125 of the hog which never sleeps
+        assert_eq!(
+            slices(&os),
+            vec![
+                (0, 0, 100),
+                (1, 100, 101),
+                (0, 101, 601),
+                (1, 601, 606),
+                (0, 606, 1006),
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_swap_when_active_empties() {
+        let os = run(
+            vec![
+                (JobBuilder::new().cpu(250), 0),
+                (JobBuilder::new().cpu(250), 0),
+                (JobBuilder::new().cpu(50), 150),
+            ],
+            O1Scheduler::new(),
+        );
+        // P2 arrives in the active array and runs before the expired P0 gets its next slice
+        let order = slices(&os).iter().map(|&(pid, ..)| pid).collect::<Vec<_>>();
+        assert_eq!(order, vec![0, 1, 2, 0, 1, 0, 1]);
+        assert_eq!(slices(&os)[3], (0, 250, 350));
+    }
+
+    #[test]
+    fn starving_expired_array_takes_interactive_tasks() {
+        let jobs = || {
+            vec![
+                (JobBuilder::new().cpu(3000), 0),
+                (JobBuilder::new().cpu(1).io(1000).cpu(300), 0),
+                (JobBuilder::new().cpu(3000), 0),
+            ]
+        };
+        // P1 wakes at 1101 with the full bonus and stays interactive through its slices
+        let os = run(jobs(), O1Scheduler::new());
+        assert!(slices(&os).contains(&(1, 1101, 1401)));
+        // P0 expired at 1101, so P1 joins it in the expired array and P2 runs
+        let os = run(jobs(), O1Scheduler::new().starvation_limit(0));
+        let slices = slices(&os);
+        let start = slices.iter().position(|&slice| slice == (1, 1101, 1200));
+        assert_eq!(start.map(|start| slices[start + 1]), Some((2, 1200, 1300)));
+    }
+}
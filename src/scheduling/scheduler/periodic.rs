@@ -0,0 +1,137 @@
+//! Rate Monotonic, Deadline Monotonic and Earliest Deadline First
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use keyed_priority_queue::KeyedPriorityQueue;
+
+use crate::scheduling::explanation::{Explanation, Key};
+use crate::scheduling::{Os, PId, Scheduler};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PeriodicPolicy {
+    /// fixed priority, the shorter period the higher priority
+    RateMonotonic,
+    /// fixed priority, the shorter relative deadline the higher priority
+    DeadlineMonotonic,
+    /// dynamic priority, the earlier absolute deadline the higher priority
+    EarliestDeadlineFirst,
+}
+
+/// Preemptive scheduler for the jobs released by periodic tasks.
+///
+/// Each job comes with its key, the period of its task under RM, the relative deadline under DM
+/// or the absolute deadline under EDF, and the index of its task which breaks ties,
+/// so equal keys keep the fixed order assumed by response-time analysis.
+pub struct PeriodicScheduler {
+    policy: PeriodicPolicy,
+    keys: HashMap<PId, (u64, usize)>,
+    ready_queue: KeyedPriorityQueue<PId, Reverse<(u64, usize)>>,
+}
+
+impl PeriodicScheduler {
+    pub fn new(policy: PeriodicPolicy, keys: HashMap<PId, (u64, usize)>) -> Self {
+        Self {
+            policy,
+            keys,
+            ready_queue: KeyedPriorityQueue::new(),
+        }
+    }
+    fn key(&self, pid: PId) -> (u64, usize) {
+        self.keys
+            .get(&pid)
+            .copied()
+            .unwrap_or((u64::MAX, usize::MAX))
+    }
+    fn explained_key(&self, value: u64) -> Key {
+        match self.policy {
+            PeriodicPolicy::RateMonotonic => Key::Period(value),
+            PeriodicPolicy::DeadlineMonotonic => Key::RelativeDeadline(value),
+            PeriodicPolicy::EarliestDeadlineFirst => Key::AbsoluteDeadline(value),
+        }
+    }
+}
+
+impl Scheduler for PeriodicScheduler {
+    fn on_process_ready(&mut self, _os: &mut Os, pid: PId) {
+        self.ready_queue.push(pid, Reverse(self.key(pid)));
+    }
+
+    fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
+        os.switch_process(self.ready_queue.pop().map(|(pid, _)| pid));
+    }
+
+    fn desc(&self) -> &'static str {
+        match self.policy {
+            PeriodicPolicy::RateMonotonic => "Rate Monotonic; Preemptive; for Periodic Task",
+            PeriodicPolicy::DeadlineMonotonic => {
+                "Deadline Monotonic; Preemptive; for Periodic Task"
+            }
+            PeriodicPolicy::EarliestDeadlineFirst => {
+                "Earliest Deadline First; Preemptive; for Periodic Task"
+            }
+        }
+    }
+
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let rule = match self.policy {
+            PeriodicPolicy::RateMonotonic => "shortest period first",
+            PeriodicPolicy::DeadlineMonotonic => "shortest relative deadline first",
+            PeriodicPolicy::EarliestDeadlineFirst => "earliest absolute deadline first",
+        };
+        let mut ready_queue = self.ready_queue.clone();
+        let mut candidates = vec![];
+        while let Some((pid, Reverse((value, _)))) = ready_queue.pop() {
+            candidates.push((pid, self.explained_key(value)));
+        }
+        Some(Explanation::new(
+            format!("{}, ties by task index", rule),
+            candidates,
+        ))
+    }
+
+    fn explain_key(&self, _os: &Os, pid: PId) -> Option<Key> {
+        Some(self.explained_key(self.key(pid).0))
+    }
+
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        os.running_process_pid()
+            .map_or(false, |running_pid| self.key(pid) < self.key(running_pid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::scheduling::analysis::{PeriodicTask, TaskSet};
+    use crate::scheduling::timer::Ticks;
+    use crate::scheduling::{Job, Process};
+
+    #[test]
+    fn explains_by_the_real_period() {
+        let task_set = TaskSet::new(vec![
+            PeriodicTask::new(1, 8).unwrap(),
+            PeriodicTask::new(1, 4).unwrap(),
+        ]);
+        assert_eq!(
+            task_set.priority_order(PeriodicPolicy::RateMonotonic),
+            vec![1, 0]
+        );
+        let job = Arc::new(Job::cpu_bound(1));
+        let processes = (0..2)
+            .map(|pid| (pid, Process::new(pid, job.clone(), Ticks::ZERO)))
+            .collect();
+        let keys = vec![(0, (8, 0)), (1, (4, 1))].into_iter().collect();
+        let scheduler = Box::new(PeriodicScheduler::new(PeriodicPolicy::RateMonotonic, keys));
+        let mut os = Os::new(processes, scheduler, "periodic").with_explanations();
+        os.run();
+        assert_eq!(
+            os.explanations()[0].candidates,
+            vec![(1, Key::Period(4)), (0, Key::Period(8))]
+        );
+        let simulation = task_set.simulate(PeriodicPolicy::RateMonotonic).unwrap();
+        assert!(simulation.deadline_misses().is_empty());
+    }
+}
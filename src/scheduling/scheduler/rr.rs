@@ -1,6 +1,7 @@
 //! Round Robin
 use std::collections::{HashMap, VecDeque};
 
+use crate::scheduling::explanation::{Explanation, Key};
 use crate::scheduling::{Os, PId, Scheduler, TICK};
 
 /// Each process is assigned a fixed time(Time Quantum/Time Slice) in cyclic way.
@@ -41,7 +42,11 @@ impl Scheduler for RoundRobinScheduler {
     }
 
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         let pid = self.ready_queue.pop_front();
+        if let Some(pid) = pid {
+            self.used_time_slice_map.insert(pid, 0);
+        }
         os.switch_process(pid);
     }
 
@@ -49,14 +54,20 @@ impl Scheduler for RoundRobinScheduler {
         "Round Robin; Preemptive; for Job or Process"
     }
 
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let candidates = self.ready_queue.iter().enumerate();
+        Some(Explanation::new(
+            format!("the head of the ready queue, for a quantum of {}", self.time_slice),
+            candidates.map(|(i, &pid)| (pid, Key::Position(i))).collect(),
+        ))
+    }
+
     fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
-        let used_time_slice = self.used_time_slice_map.get(&pid).copied().unwrap_or(0);
+        let used_time_slice = self.used_time_slice_map.get(&pid).copied().unwrap_or(0) + TICK;
+        self.used_time_slice_map.insert(pid, used_time_slice);
         if used_time_slice >= self.time_slice && os.is_process_running(pid) {
             self.ready_queue.push_back(pid);
-            self.used_time_slice_map.insert(pid, 0);
             self.switch_process(os);
-        } else {
-            self.used_time_slice_map.insert(pid, used_time_slice + TICK);
         }
     }
 }
@@ -3,14 +3,21 @@ use std::cmp::Reverse;
 
 use keyed_priority_queue::KeyedPriorityQueue;
 
+use crate::scheduling::explanation::{Explanation, Key};
 use crate::scheduling::{Os, PId, Scheduler};
 
 /// Process which have the shortest burst time are scheduled first.
 /// If two processes have the same bust time then FCFS is used to break the tie.
 /// It is a non-preemptive scheduling algorithm.
+///
+/// The burst time is the CPU time left when the process becomes ready,
+/// see [`Process::remaining_time`].
+///
+/// [`Process::remaining_time`]: crate::scheduling::Process::remaining_time
 #[derive(Default, Clone)]
 pub struct ShortestJobFirstScheduler {
-    ready_queue: KeyedPriorityQueue<PId, Reverse<u64>>,
+    ready_queue: KeyedPriorityQueue<PId, Reverse<(u64, usize)>>,
+    ready_count: usize,
 }
 
 impl ShortestJobFirstScheduler {
@@ -22,12 +29,15 @@ impl ShortestJobFirstScheduler {
 impl Scheduler for ShortestJobFirstScheduler {
     fn on_process_ready(&mut self, os: &mut Os, pid: usize) {
         if let Some(process) = os.get_process(pid) {
-            let burst_time = process.burst_time();
-            self.ready_queue.push(pid, Reverse(burst_time));
+            let burst_time = process.remaining_time();
+            self.ready_queue
+                .push(pid, Reverse((burst_time, self.ready_count)));
+            self.ready_count += 1;
         }
     }
 
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         os.switch_process(self.ready_queue.pop().map(|(pid, _)| pid));
     }
 
@@ -35,4 +45,13 @@ impl Scheduler for ShortestJobFirstScheduler {
         "Shortest Job First; Non-Preemptive; for Job"
     }
 
+    /// Pops a copy of the ready queue, so the candidates come in the order they would run.
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let mut ready_queue = self.ready_queue.clone();
+        let mut candidates = vec![];
+        while let Some((pid, Reverse((burst_time, _)))) = ready_queue.pop() {
+            candidates.push((pid, Key::BurstTime(burst_time)));
+        }
+        Some(Explanation::new("shortest burst time first, ties by arrival", candidates))
+    }
 }
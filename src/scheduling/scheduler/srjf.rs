@@ -4,29 +4,39 @@ use std::cmp::Reverse;
 
 use keyed_priority_queue::KeyedPriorityQueue;
 
+use crate::scheduling::explanation::{Explanation, Key};
 use crate::scheduling::{Os, PId, Scheduler};
 
 /// It is preemptive mode of SJF algorithm in which jobs are schedule according to shortest remaining time.
+/// A process which becomes ready preempts the running one as soon as its remaining time is shorter.
+/// Ties are broken by FCFS, and the running process is never preempted by an equal one.
 #[derive(Default, Clone)]
 pub struct ShortestRemainingJobFirstScheduler {
-    ready_queue: KeyedPriorityQueue<PId, Reverse<u64>>,
+    ready_queue: KeyedPriorityQueue<PId, Reverse<(u64, usize)>>,
+    ready_count: usize,
 }
 
 impl ShortestRemainingJobFirstScheduler {
     pub fn new() -> Self {
         Self::default()
     }
+    fn push(&mut self, pid: PId, remaining_time: u64) {
+        self.ready_queue
+            .push(pid, Reverse((remaining_time, self.ready_count)));
+        self.ready_count += 1;
+    }
 }
 
 impl Scheduler for ShortestRemainingJobFirstScheduler {
     fn on_process_ready(&mut self, os: &mut Os, pid: usize) {
         if let Some(process) = os.get_process(pid) {
-            self.ready_queue
-                .push(pid, Reverse(process.remaining_time()));
+            let remaining_time = process.remaining_time();
+            self.push(pid, remaining_time);
         }
     }
 
     fn switch_process(&mut self, os: &mut Os) {
+        os.explain(self);
         os.switch_process(self.ready_queue.pop().map(|(pid, _)| pid));
     }
 
@@ -34,17 +44,26 @@ impl Scheduler for ShortestRemainingJobFirstScheduler {
         "Shortest Remaining Job First; Preemptive; for Job"
     }
 
-    fn on_process_burst(&mut self, os: &mut Os, pid: PId) {
-        let process_remaining_time = os.get_process(pid).map(|p| p.remaining_time()).unwrap_or(0);
-        if self
-            .ready_queue
-            .peek()
-            .map_or(false, |(_, top_remaining_time)| {
-                top_remaining_time.gt(&&Reverse(process_remaining_time))
-            })
-        {
-            self.switch_process(os);
-            self.ready_queue.push(pid, Reverse(process_remaining_time));
+    /// Pops a copy of the ready queue, so the candidates come in the order they would run.
+    fn explain(&self, _os: &Os) -> Option<Explanation> {
+        let mut ready_queue = self.ready_queue.clone();
+        let mut candidates = vec![];
+        while let Some((pid, Reverse((remaining_time, _)))) = ready_queue.pop() {
+            candidates.push((pid, Key::RemainingTime(remaining_time)));
+        }
+        Some(Explanation::new("shortest remaining time first, ties by arrival", candidates))
+    }
+    fn explain_key(&self, os: &Os, pid: PId) -> Option<Key> {
+        os.get_process(pid)
+            .map(|p| Key::RemainingTime(p.remaining_time()))
+    }
+    fn should_preempt(&self, os: &Os, pid: PId) -> bool {
+        let remaining_time = |pid| os.get_process(pid).map(|p| p.remaining_time());
+        match (remaining_time(pid), os.running_process_pid().and_then(remaining_time)) {
+            (Some(ready_remaining_time), Some(running_remaining_time)) => {
+                ready_remaining_time.lt(&running_remaining_time)
+            }
+            _ => false,
         }
     }
 }
@@ -1,7 +1,10 @@
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     CpuBound(u64),
     IoBound(u64),
+    /// an interactive user thinking before the next request, blocks like I/O
+    Think(u64),
 }
 
 impl Statement {
@@ -11,16 +14,23 @@ impl Statement {
     pub fn io_bound(duration: u64) -> Self {
         Statement::IoBound(duration)
     }
+    pub fn think(duration: u64) -> Self {
+        Statement::Think(duration)
+    }
     pub fn is_cpu_bound(&self) -> bool {
         matches!(self, Statement::CpuBound(_))
     }
     pub fn is_io_bound(&self) -> bool {
         matches!(self, Statement::IoBound(_))
     }
+    pub fn is_think(&self) -> bool {
+        matches!(self, Statement::Think(_))
+    }
     pub fn duration(&self) -> u64 {
         match self {
             Statement::CpuBound(duration) => *duration,
             Statement::IoBound(duration) => *duration,
+            Statement::Think(duration) => *duration,
         }
     }
 }
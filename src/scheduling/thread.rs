@@ -0,0 +1,192 @@
+//! Multithreaded Processes
+//!
+//! A [`MultithreadedProcess`] holds one statement stream per thread
+//! and is turned into the tasks seen by the kernel scheduler according to its [`ThreadModel`].
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::scheduling::job::JobBuilder;
+use crate::scheduling::report::Report;
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{Job, Os, PId, Process};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ThreadModel {
+    /// One-to-one, each thread is a task scheduled independently
+    /// and an I/O block only affects that thread.
+    KernelLevel,
+    /// Many-to-one, the threads are multiplexed on a single task by a user-level library,
+    /// which only switches threads when the running one blocks or finishes,
+    /// so a blocking thread blocks the whole process.
+    UserLevel,
+}
+
+impl ThreadModel {
+    pub fn desc(&self) -> &'static str {
+        match self {
+            ThreadModel::KernelLevel => "Kernel-Level (1:1)",
+            ThreadModel::UserLevel => "User-Level (N:1)",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MultithreadedProcess {
+    pub id: PId,
+    pub arrival_time: Ticks,
+    pub model: ThreadModel,
+    pub threads: Vec<Arc<Job>>,
+}
+
+impl MultithreadedProcess {
+    pub fn new(id: PId, arrival_time: Ticks, model: ThreadModel) -> Self {
+        Self {
+            id,
+            arrival_time,
+            model,
+            threads: vec![],
+        }
+    }
+    pub fn thread(mut self, job: Job) -> Self {
+        self.threads.push(Arc::new(job));
+        self
+    }
+    /// Same threads under another model.
+    pub fn with_model(mut self, model: ThreadModel) -> Self {
+        self.model = model;
+        self
+    }
+    /// The single statement stream the user-level library runs on its task.
+    ///
+    /// Threads take turns in order, each runs its CPU burst and the I/O burst following it,
+    /// during which nothing else of the process can run.
+    fn user_level_job(&self) -> Job {
+        let mut threads = self
+            .threads
+            .iter()
+            .map(|job| job.statements.iter().copied().collect::<VecDeque<_>>())
+            .collect::<VecDeque<_>>();
+        let mut builder = JobBuilder::new();
+        while let Some(mut statements) = threads.pop_front() {
+            while let Some(statement) = statements.pop_front() {
+                builder = builder.statement(statement);
+                if !statement.is_cpu_bound() {
+                    break;
+                }
+            }
+            if !statements.is_empty() {
+                threads.push_back(statements);
+            }
+        }
+        builder.build()
+    }
+    /// Tasks for the kernel scheduler, with ids allocated from `tids`.
+    pub fn tasks(&self, tids: &mut impl Iterator<Item = PId>) -> Vec<Process> {
+        match self.model {
+            ThreadModel::KernelLevel => self
+                .threads
+                .iter()
+                .zip(tids)
+                .map(|(job, tid)| {
+                    Process::new(tid, job.clone(), self.arrival_time).with_group(self.id)
+                })
+                .collect(),
+            ThreadModel::UserLevel => tids
+                .next()
+                .map(|tid| {
+                    let job = Arc::new(self.user_level_job());
+                    Process::new(tid, job, self.arrival_time).with_group(self.id)
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Tasks of all the processes for [`Os::new`], task ids are allocated in order from 0.
+pub fn tasks(processes: &[MultithreadedProcess]) -> IndexMap<PId, Process> {
+    let mut tids = 0..;
+    processes
+        .iter()
+        .flat_map(|process| process.tasks(&mut tids))
+        .map(|task| (task.id, task))
+        .collect()
+}
+
+/// Per process stats of a run over [`tasks`].
+pub fn process_stats_report(os: &Os, processes: &[MultithreadedProcess]) -> Report {
+    let mut report = Report::new(&[
+        "PId",
+        "Model",
+        "Threads",
+        "Tasks",
+        "Arrival",
+        "Completion",
+        "Turn Around",
+        "CPU Time",
+        "Ave Task Waiting",
+    ]);
+    for process in processes {
+        let tasks = os
+            .processes()
+            .filter(|task| task.group() == process.id)
+            .collect::<Vec<_>>();
+        let completion_time = tasks
+            .iter()
+            .map(|task| task.completion_time())
+            .max()
+            .unwrap_or(process.arrival_time);
+        let total_waiting_time = tasks.iter().map(|task| task.waiting_time()).sum::<u64>();
+        report.add_row(vec![
+            process.id.to_string(),
+            process.model.desc().to_string(),
+            process.threads.len().to_string(),
+            tasks.len().to_string(),
+            process.arrival_time.to_string(),
+            completion_time.to_string(),
+            completion_time.since(process.arrival_time).to_string(),
+            tasks
+                .iter()
+                .map(|task| task.burst_time())
+                .sum::<u64>()
+                .to_string(),
+            total_waiting_time
+                .checked_div(tasks.len() as u64)
+                .unwrap_or(0)
+                .to_string(),
+        ]);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scheduling::FirstComeFirstServeScheduler;
+
+    use super::*;
+
+    fn turn_around_time(model: ThreadModel) -> u64 {
+        let thread = || JobBuilder::new().cpu(2).io(4).cpu(2).build();
+        let processes = [MultithreadedProcess::new(0, Ticks::ZERO, model)
+            .thread(thread())
+            .thread(thread())];
+        let mut os = Os::new(
+            tasks(&processes),
+            Box::new(FirstComeFirstServeScheduler::new()),
+            model.desc(),
+        );
+        os.run();
+        os.processes()
+            .map(|task| task.completion_time().0)
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn blocking_thread_blocks_user_level_process() {
+        assert_eq!(turn_around_time(ThreadModel::KernelLevel), 10);
+        assert_eq!(turn_around_time(ThreadModel::UserLevel), 16);
+    }
+}
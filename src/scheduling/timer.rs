@@ -0,0 +1,381 @@
+//! Timers
+//!
+//! [`Os`](crate::scheduling::Os) keeps arriving processes and processes blocked on I/O in a [`Timer`],
+//! which delivers them back once the simulated clock reaches their deadlines.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+/// An instant of simulated time, counted in scheduler ticks from the start of the run.
+///
+/// Durations stay plain `u64` tick counts: adding one to an instant gives a later instant,
+/// and [`Ticks::since`] gives the duration between two instants.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ticks(pub u64);
+
+impl Ticks {
+    pub const ZERO: Ticks = Ticks(0);
+
+    /// Ticks elapsed from `earlier` to `self`, 0 if `earlier` is later.
+    pub fn since(self, earlier: Ticks) -> u64 {
+        self.0.saturating_sub(earlier.0)
+    }
+}
+
+impl From<u64> for Ticks {
+    fn from(ticks: u64) -> Self {
+        Ticks(ticks)
+    }
+}
+
+impl Add<u64> for Ticks {
+    type Output = Ticks;
+
+    fn add(self, duration: u64) -> Ticks {
+        Ticks(self.0 + duration)
+    }
+}
+
+impl AddAssign<u64> for Ticks {
+    fn add_assign(&mut self, duration: u64) {
+        self.0 += duration;
+    }
+}
+
+impl fmt::Display for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub trait Timer<T> {
+    /// Schedules `value` to expire at `deadline`, a deadline already reached expires on the next pop.
+    fn insert(&mut self, deadline: Ticks, value: T);
+    /// Moves the timer forward to `now`, it never goes back.
+    fn advance(&mut self, now: Ticks);
+    /// Pops an expired value, the earliest deadline first and FIFO for the same deadline.
+    fn pop_expired(&mut self) -> Option<T>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+struct Entry<T> {
+    deadline: Ticks,
+    /// insertion order, breaks the ties of deadlines
+    seq: u64,
+    value: T,
+}
+
+impl<T> Entry<T> {
+    fn key(&self) -> (Ticks, u64) {
+        (self.deadline, self.seq)
+    }
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Min-heap of deadlines, `O(log n)` insert and pop.
+pub struct HeapTimer<T> {
+    now: Ticks,
+    seq: u64,
+    heap: BinaryHeap<Reverse<Entry<T>>>,
+}
+
+impl<T> HeapTimer<T> {
+    pub fn new() -> Self {
+        Self {
+            now: Ticks::ZERO,
+            seq: 0,
+            heap: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<T> Default for HeapTimer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Timer<T> for HeapTimer<T> {
+    fn insert(&mut self, deadline: Ticks, value: T) {
+        self.heap.push(Reverse(Entry {
+            deadline,
+            seq: self.seq,
+            value,
+        }));
+        self.seq += 1;
+    }
+
+    fn advance(&mut self, now: Ticks) {
+        self.now = self.now.max(now);
+    }
+
+    fn pop_expired(&mut self) -> Option<T> {
+        if self.heap.peek()?.0.deadline > self.now {
+            return None;
+        }
+        self.heap.pop().map(|Reverse(entry)| entry.value)
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+}
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 4;
+
+/// Hierarchical timing wheel, `O(1)` insert and amortized `O(1)` per tick.
+///
+/// Level `i` has 64 slots of `64^i` ticks each. An entry sits on the lowest level which covers its
+/// distance to the deadline and cascades down as the wheel turns, entries farther than the whole
+/// wheel wait in an overflow list, so there is no max timeout.
+pub struct TimingWheel<T> {
+    now: Ticks,
+    seq: u64,
+    len: usize,
+    /// entries on each level
+    level_lens: [usize; LEVELS],
+    levels: Vec<Vec<Vec<Entry<T>>>>,
+    overflow: Vec<Entry<T>>,
+    expired: VecDeque<Entry<T>>,
+}
+
+impl<T> TimingWheel<T> {
+    pub fn new() -> Self {
+        Self {
+            now: Ticks::ZERO,
+            seq: 0,
+            len: 0,
+            level_lens: [0; LEVELS],
+            levels: (0..LEVELS)
+                .map(|_| (0..SLOTS).map(|_| vec![]).collect())
+                .collect(),
+            overflow: vec![],
+            expired: VecDeque::new(),
+        }
+    }
+    fn slot(deadline: Ticks, level: usize) -> usize {
+        ((deadline.0 >> (SLOT_BITS * level as u32)) as usize) & (SLOTS - 1)
+    }
+    fn place(&mut self, entry: Entry<T>) -> Option<Entry<T>> {
+        if entry.deadline <= self.now {
+            return Some(entry);
+        }
+        let distance = entry.deadline.since(self.now);
+        match (0..LEVELS).find(|&level| distance < 1 << (SLOT_BITS * (level as u32 + 1))) {
+            Some(level) => {
+                let slot = Self::slot(entry.deadline, level);
+                self.levels[level][slot].push(entry);
+                self.level_lens[level] += 1;
+            }
+            None => self.overflow.push(entry),
+        }
+        None
+    }
+    fn expire(&mut self, mut entries: Vec<Entry<T>>) {
+        entries.sort();
+        self.expired.extend(entries);
+    }
+    /// Turns the wheel by one tick.
+    fn turn(&mut self) {
+        self.now += 1;
+        let mut due = vec![];
+        if self.now.0 % (1 << (SLOT_BITS * LEVELS as u32)) == 0 {
+            for entry in std::mem::take(&mut self.overflow) {
+                due.extend(self.place(entry));
+            }
+        }
+        for level in (1..LEVELS).rev() {
+            if self.now.0 % (1 << (SLOT_BITS * level as u32)) != 0 {
+                continue;
+            }
+            let slot = Self::slot(self.now, level);
+            let entries = std::mem::take(&mut self.levels[level][slot]);
+            self.level_lens[level] -= entries.len();
+            for entry in entries {
+                due.extend(self.place(entry));
+            }
+        }
+        let slot = Self::slot(self.now, 0);
+        self.level_lens[0] -= self.levels[0][slot].len();
+        due.append(&mut self.levels[0][slot]);
+        self.expire(due);
+    }
+}
+
+impl<T> Default for TimingWheel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Timer<T> for TimingWheel<T> {
+    fn insert(&mut self, deadline: Ticks, value: T) {
+        let entry = Entry {
+            deadline,
+            seq: self.seq,
+            value,
+        };
+        self.seq += 1;
+        self.len += 1;
+        if let Some(entry) = self.place(entry) {
+            let index = self
+                .expired
+                .iter()
+                .position(|expired| expired.key() > entry.key())
+                .unwrap_or(self.expired.len());
+            self.expired.insert(index, entry);
+        }
+    }
+
+    fn advance(&mut self, now: Ticks) {
+        while self.now < now {
+            // nothing happens until the lowest occupied level, or the overflow, cascades next
+            let level = (0..LEVELS)
+                .find(|&level| self.level_lens[level] > 0)
+                .unwrap_or(LEVELS);
+            if level > 0 {
+                let span = 1 << (SLOT_BITS * level as u32);
+                let skip_to = if level == LEVELS && self.overflow.is_empty() {
+                    now
+                } else {
+                    now.min(Ticks((self.now.0 / span + 1) * span - 1))
+                };
+                self.now = self.now.max(skip_to);
+                if self.now >= now {
+                    break;
+                }
+            }
+            self.turn();
+        }
+    }
+
+    fn pop_expired(&mut self) -> Option<T> {
+        let entry = self.expired.pop_front()?;
+        self.len -= 1;
+        Some(entry.value)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(timer: &mut dyn Timer<usize>, now: u64) -> Vec<usize> {
+        timer.advance(Ticks(now));
+        std::iter::from_fn(|| timer.pop_expired()).collect()
+    }
+
+    #[test]
+    fn wheel_agrees_with_heap() {
+        let rng = fastrand::Rng::new();
+        rng.seed(38);
+        let mut heap = HeapTimer::new();
+        let mut wheel = TimingWheel::new();
+        let mut now = 0;
+        for value in 0..5000 {
+            let deadline = match rng.u8(..10) {
+                0 => now + rng.u64(..50_000_000),
+                1..=3 => now,
+                _ => now + rng.u64(..300),
+            };
+            heap.insert(Ticks(deadline), value);
+            wheel.insert(Ticks(deadline), value);
+            if rng.bool() {
+                now += rng.u64(..100);
+                assert_eq!(drain(&mut heap, now), drain(&mut wheel, now));
+            }
+        }
+        now += 50_000_000;
+        let expired = drain(&mut heap, now);
+        assert_eq!(expired, drain(&mut wheel, now));
+        assert!(heap.is_empty() && wheel.is_empty());
+    }
+
+    #[test]
+    fn same_deadline_is_fifo() {
+        let mut wheel = TimingWheel::new();
+        for value in 0..3 {
+            wheel.insert(Ticks(70), value);
+        }
+        wheel.insert(Ticks(5), 3);
+        assert_eq!(drain(&mut wheel, 4), Vec::<usize>::new());
+        assert_eq!(drain(&mut wheel, 70), vec![3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn os_runs_the_same_on_both_timers() {
+        use std::sync::Arc;
+
+        use crate::scheduling::job::JobBuilder;
+        use crate::scheduling::{Os, Process, RoundRobinScheduler};
+
+        let processes = (0..6)
+            .map(|pid| {
+                let job = JobBuilder::new()
+                    .seed(pid as u64)
+                    .random_bursts(3, 1..20, 1..100)
+                    .build();
+                // far beyond the max timeout of the former timer
+                let arrival_time = if pid == 5 { 1_000_500 } else { pid as u64 * 3 };
+                (pid, Process::new(pid, Arc::new(job), Ticks(arrival_time)))
+            })
+            .collect::<indexmap::IndexMap<_, _>>();
+        let run = |os: Os| {
+            let mut os = os;
+            os.run();
+            os.timeline().to_vec()
+        };
+        let scheduler = || Box::new(RoundRobinScheduler::new(4));
+        let heap = run(Os::new(processes.clone(), scheduler(), "heap"));
+        let wheel = run(Os::new(processes, scheduler(), "wheel").with_timer(TimingWheel::new()));
+        assert_eq!(heap, wheel);
+        assert_eq!(heap.last().map(|slice| slice.pid), Some(Some(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "before the first tick")]
+    fn timer_is_replaced_before_the_first_tick() {
+        use std::sync::Arc;
+
+        use crate::scheduling::job::JobBuilder;
+        use crate::scheduling::{Os, Process, RoundRobinScheduler};
+
+        let job = Arc::new(JobBuilder::new().cpu(2).io(10).cpu(2).build());
+        let processes = vec![(0, Process::new(0, job, Ticks::ZERO))]
+            .into_iter()
+            .collect();
+        let mut os = Os::new(processes, Box::new(RoundRobinScheduler::new(4)), "wheel");
+        os.tick();
+        os.with_timer(TimingWheel::new());
+    }
+}
@@ -0,0 +1,238 @@
+//! Trace Import
+//!
+//! Replays CPU and I/O bursts recorded from real programs.
+//! A trace is a CSV of `pid, arrival, kind, duration` rows, where `kind` is `cpu`, `io` or `think`:
+//!
+//! ```text
+//! # pid, arrival, kind, duration
+//! 0, 0, cpu, 12
+//! 0, 0, io, 40
+//! 1, 3, cpu, 7
+//! 0, 0, cpu, 5
+//! ```
+//!
+//! The rows of a process are its bursts in order and may be interleaved with other processes.
+//! Blank lines, `#` comments and a leading `pid,...` header are skipped.
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::scheduling::statement::Statement;
+use crate::scheduling::job::JobBuilder;
+use crate::scheduling::timer::Ticks;
+use crate::scheduling::{PId, Process};
+
+#[derive(Debug)]
+pub enum TraceError {
+    Io(std::io::Error),
+    /// no burst at all
+    Empty,
+    Invalid {
+        /// 1-based line number
+        line: usize,
+        content: String,
+        reason: InvalidLine,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidLine {
+    FieldCount(usize),
+    PId(String),
+    Arrival(String),
+    Kind(String),
+    Duration(String),
+    ZeroDuration,
+    /// the arrival differs from the one on the first row of the process
+    ArrivalMismatch(Ticks),
+    /// a process has to be dispatched before it can issue I/O
+    StartsWithIo,
+}
+
+impl fmt::Display for InvalidLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidLine::FieldCount(count) => write!(
+                f,
+                "expected 4 fields `pid, arrival, kind, duration`, found {}",
+                count
+            ),
+            InvalidLine::PId(pid) => write!(f, "invalid pid `{}`", pid),
+            InvalidLine::Arrival(arrival) => write!(f, "invalid arrival `{}`", arrival),
+            InvalidLine::Kind(kind) => {
+                write!(f, "unknown burst kind `{}`, expected `cpu`, `io` or `think`", kind)
+            }
+            InvalidLine::Duration(duration) => write!(f, "invalid duration `{}`", duration),
+            InvalidLine::ZeroDuration => write!(f, "duration must be positive"),
+            InvalidLine::ArrivalMismatch(arrival) => {
+                write!(f, "arrival differs from the earlier one `{}` of the process", arrival)
+            }
+            InvalidLine::StartsWithIo => write!(f, "the first burst of a process must be `cpu`"),
+        }
+    }
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::Io(err) => write!(f, "failed to read trace: {}", err),
+            TraceError::Empty => write!(f, "trace has no bursts"),
+            TraceError::Invalid {
+                line,
+                content,
+                reason,
+            } => write!(f, "line {}: `{}`: {}", line, content, reason),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl From<std::io::Error> for TraceError {
+    fn from(err: std::io::Error) -> Self {
+        TraceError::Io(err)
+    }
+}
+
+struct Row {
+    pid: PId,
+    arrival_time: Ticks,
+    statement: Statement,
+}
+
+fn parse_row(content: &str) -> Result<Row, InvalidLine> {
+    let fields = content.split(',').map(str::trim).collect::<Vec<_>>();
+    if fields.len() != 4 {
+        return Err(InvalidLine::FieldCount(fields.len()));
+    }
+    let pid = fields[0]
+        .parse::<PId>()
+        .map_err(|_| InvalidLine::PId(fields[0].to_string()))?;
+    let arrival_time = fields[1]
+        .parse::<u64>()
+        .map(Ticks)
+        .map_err(|_| InvalidLine::Arrival(fields[1].to_string()))?;
+    let duration = fields[3]
+        .parse::<u64>()
+        .map_err(|_| InvalidLine::Duration(fields[3].to_string()))?;
+    if duration == 0 {
+        return Err(InvalidLine::ZeroDuration);
+    }
+    let statement = match fields[2].to_ascii_lowercase().as_str() {
+        "cpu" => Statement::cpu_bound(duration),
+        "io" => Statement::io_bound(duration),
+        "think" => Statement::think(duration),
+        _ => return Err(InvalidLine::Kind(fields[2].to_string())),
+    };
+    Ok(Row {
+        pid,
+        arrival_time,
+        statement,
+    })
+}
+
+fn is_header(content: &str) -> bool {
+    content
+        .split(',')
+        .next()
+        .map_or(false, |field| field.trim().eq_ignore_ascii_case("pid"))
+}
+
+/// Parses a trace into processes ordered by their first row.
+///
+/// Consecutive bursts of the same kind are merged, so the totals are kept exactly.
+pub fn parse(input: &str) -> Result<IndexMap<PId, Process>, TraceError> {
+    let mut jobs: IndexMap<PId, (Ticks, JobBuilder)> = IndexMap::new();
+    let mut is_first_row = true;
+    for (index, content) in input.lines().enumerate() {
+        let content = content.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if is_first_row && is_header(content) {
+            is_first_row = false;
+            continue;
+        }
+        is_first_row = false;
+        let invalid = |reason| TraceError::Invalid {
+            line: index + 1,
+            content: content.to_string(),
+            reason,
+        };
+        let row = parse_row(content).map_err(invalid)?;
+        match jobs.get(&row.pid) {
+            None if !row.statement.is_cpu_bound() => {
+                return Err(invalid(InvalidLine::StartsWithIo));
+            }
+            Some((arrival_time, _)) if *arrival_time != row.arrival_time => {
+                return Err(invalid(InvalidLine::ArrivalMismatch(*arrival_time)));
+            }
+            _ => {}
+        }
+        let (_, builder) = jobs
+            .entry(row.pid)
+            .or_insert_with(|| (row.arrival_time, JobBuilder::new()));
+        *builder = std::mem::take(builder).statement(row.statement);
+    }
+    if jobs.is_empty() {
+        return Err(TraceError::Empty);
+    }
+    Ok(jobs
+        .into_iter()
+        .map(|(pid, (arrival_time, builder))| {
+            (pid, Process::new(pid, Arc::new(builder.build()), arrival_time))
+        })
+        .collect())
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<IndexMap<PId, Process>, TraceError> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_interleaved_trace() {
+        let processes = parse(
+            "pid,arrival,kind,duration\n\
+             # recorded by strace\n\
+             0, 0, cpu, 3\n\
+             1, 2, CPU, 4\n\
+             0, 0, io, 5\n\
+             0, 0, io, 1\n\
+             \n\
+             0, 0, cpu, 2\n",
+        )
+        .unwrap();
+        assert_eq!(processes.keys().copied().collect::<Vec<_>>(), vec![0, 1]);
+        let job = &processes[&0].job;
+        assert_eq!(job.statements.len(), 3);
+        assert_eq!(job.total_cpu_duration, 5);
+        assert_eq!(job.total_io_duration, 6);
+        assert!(job.is_io_bound());
+        assert_eq!(processes[&1].arrival_time(), Ticks(2));
+    }
+
+    #[test]
+    fn report_offending_line() {
+        let err = parse("0,0,cpu,3\n\n0,0,disk,5\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3: `0,0,disk,5`: unknown burst kind `disk`, expected `cpu`, `io` or `think`"
+        );
+        let err = parse("0,0,cpu,3\n0,1,io,5\n").unwrap_err();
+        assert!(matches!(
+            err,
+            TraceError::Invalid {
+                line: 2,
+                reason: InvalidLine::ArrivalMismatch(Ticks(0)),
+                ..
+            }
+        ));
+        assert!(matches!(parse("# nothing\n"), Err(TraceError::Empty)));
+    }
+}
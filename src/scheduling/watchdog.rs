@@ -0,0 +1,112 @@
+//! Starvation Watchdog
+use std::fmt;
+
+use crate::scheduling::timer::Ticks;
+
+/// Watches a run of [`Os`](crate::scheduling::Os),
+/// flags starving processes and aborts runs that make no progress.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Watchdog {
+    /// flags a process once its continuous wait in the ready state exceeds it
+    pub starvation_threshold: Option<u64>,
+    /// aborts the run once no process has made progress for that many ticks
+    pub stall_limit: Option<u64>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn starvation_threshold(mut self, starvation_threshold: u64) -> Self {
+        self.starvation_threshold = Some(starvation_threshold);
+        self
+    }
+    pub fn stall_limit(mut self, stall_limit: u64) -> Self {
+        self.stall_limit = Some(stall_limit);
+        self
+    }
+}
+
+/// Why a run was aborted by the watchdog.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stall {
+    pub clock: Ticks,
+    pub stalled_ticks: u64,
+}
+
+impl fmt::Display for Stall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Aborted at Clock[{}]: no progress for {} ticks",
+            self.clock, self.stalled_ticks
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scheduling::scenario::Scenario;
+    use crate::scheduling::{LongestJobFirstScheduler, Os, PId, Scheduler};
+
+    use super::*;
+
+    /// Runs process 0 and loses every other ready process.
+    struct LossyScheduler;
+
+    impl Scheduler for LossyScheduler {
+        fn on_process_ready(&mut self, os: &mut Os, pid: PId) {
+            if pid == 0 {
+                os.switch_process(Some(pid));
+            }
+        }
+        fn switch_process(&mut self, os: &mut Os) {
+            os.switch_process(None);
+        }
+        fn desc(&self) -> &'static str {
+            "Lossy"
+        }
+    }
+
+    #[test]
+    fn flags_starving_short_job() {
+        let watchdog = Watchdog::new().starvation_threshold(20);
+        let os = Scenario::new("starvation", Box::new(LongestJobFirstScheduler::new()))
+            .process(0, 0, 10)
+            .process(1, 1, 2)
+            .process(2, 2, 10)
+            .process(3, 3, 10)
+            .configure(move |os| os.with_watchdog(watchdog))
+            .run()
+            .into_os();
+        assert_eq!(os.longest_ready_wait(1), 29);
+        assert_eq!(os.starved_processes().keys().collect::<Vec<_>>(), vec![&1]);
+        assert!(os.stall().is_none());
+    }
+
+    #[test]
+    fn aborts_run_without_progress() {
+        let watchdog = Watchdog::new().stall_limit(10);
+        let os = Scenario::new("lost", Box::new(LossyScheduler))
+            .process(0, 0, 4)
+            .process(1, 0, 3)
+            .configure(move |os| os.with_watchdog(watchdog))
+            .run()
+            .into_os();
+        assert_eq!(
+            os.stall(),
+            Some(Stall {
+                clock: Ticks(14),
+                stalled_ticks: 10
+            })
+        );
+        assert!(!os.get_process(1).unwrap().is_completed());
+        // the lost process is left out of the averages
+        let stats = os.stats();
+        assert_eq!(
+            (stats.average_turn_around_time, stats.average_waiting_time),
+            (4, 0)
+        );
+        assert!(os.stats_cells(stats, None)[1].ends_with("(Aborted)"));
+    }
+}